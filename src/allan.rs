@@ -0,0 +1,247 @@
+//! Allan deviation of conversion results.
+//!
+//! The averaging time τ is a multiple `m` of the nominal conversion period. Deviations are in codes.
+//!
+//! Long series are processed by the streaming accumulators [`Allan`] and [`OverlappingAllan`], which evaluate
+//! octave-spaced averaging times `m = 1, 2, 4, …` using bounded memory. Captured series can also be evaluated
+//! directly using [`deviation`] and [`overlapping_deviation`].
+//!
+//! ```rust
+//! use max112x::{allan, ConversionMode, ConversionRate};
+//!
+//! let mut adev = allan::Allan::<8>::new(ConversionRate::Hz1000, ConversionMode::Continuous);
+//! for i in 0..1024 {
+//!   adev.push(if i % 2 == 0 { 10 } else { -10 });
+//! }
+//!
+//! // Alternating results cancel out when averaging over two or more periods.
+//! let points = adev.points().collect::<Vec<_>>();
+//! assert_eq!(points[0].tau, 0.001);
+//! assert_eq!(points[0].deviation, 20.0 / 2f32.sqrt());
+//! assert_eq!(points[1].deviation, 0.0);
+//! ```
+
+use crate::{ConversionMode, ConversionRate};
+
+/// Allan deviation at one averaging time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+  /// Averaging factor, i.e. the number of conversion periods averaged.
+  pub m: u32,
+  /// Averaging time in seconds.
+  pub tau: f32,
+  /// Allan deviation in codes.
+  pub deviation: f32,
+  /// Number of differences the deviation is based on.
+  pub count: u64,
+}
+
+/// Get the averaging time in seconds for an averaging factor at the nominal conversion rate.
+pub fn tau(rate: ConversionRate, mode: ConversionMode, m: u32) -> f32 {
+  let hz = rate.hz(mode);
+  (u64::from(m) * hz.den) as f32 / hz.num as f32
+}
+
+/// Compute the non-overlapping Allan deviation of a series for the averaging factor `m`.
+///
+/// Returns `None` if the series is shorter than two averaging intervals.
+pub fn deviation(values: &[i32], m: usize) -> Option<f32> {
+  if m == 0 {
+    return None
+  }
+
+  let mut averages = values.chunks_exact(m).map(|chunk| chunk.iter().map(|&v| f64::from(v)).sum::<f64>() / m as f64);
+
+  let mut prev = averages.next()?;
+  let (mut sum, mut count) = (0.0, 0u32);
+  for average in averages {
+    sum += (average - prev) * (average - prev);
+    count += 1;
+    prev = average;
+  }
+
+  (count > 0).then(|| libm::sqrt(sum / f64::from(count) / 2.0) as f32)
+}
+
+/// Compute the overlapping Allan deviation of a series for the averaging factor `m`.
+///
+/// Returns `None` if the series is shorter than two averaging intervals.
+pub fn overlapping_deviation(values: &[i32], m: usize) -> Option<f32> {
+  if m == 0 || values.len() < 2 * m {
+    return None
+  }
+
+  let value = |i: usize| f64::from(values[i]);
+
+  // Sliding window sum over `values[i + m..i + 2m]` minus the sum over `values[i..i + m]`.
+  let mut diff = (m..2 * m).map(value).sum::<f64>() - (0..m).map(value).sum::<f64>();
+  let mut sum = diff * diff;
+
+  for i in 1..=values.len() - 2 * m {
+    diff += value(i + 2 * m - 1) - 2.0 * value(i + m - 1) + value(i - 1);
+    sum += diff * diff;
+  }
+
+  let count = (values.len() - 2 * m + 1) as f64;
+  Some(libm::sqrt(sum / count / (2.0 * (m * m) as f64)) as f32)
+}
+
+/// Accumulator state of one octave.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Octave {
+  prev: Option<f64>,
+  pending: Option<f64>,
+  sum: f64,
+  count: u64,
+}
+
+impl Octave {
+  const fn new() -> Self {
+    Self { prev: None, pending: None, sum: 0.0, count: 0 }
+  }
+}
+
+/// Streaming non-overlapping Allan deviation for `OCTAVES` octave-spaced averaging factors `m = 1, 2, 4, …`.
+///
+/// Each octave averages pairs of averages from the octave below, so only a constant amount of state is kept per
+/// octave.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allan<const OCTAVES: usize> {
+  rate: ConversionRate,
+  mode: ConversionMode,
+  offset: Option<i32>,
+  octaves: [Octave; OCTAVES],
+}
+
+impl<const OCTAVES: usize> Allan<OCTAVES> {
+  /// Create an empty accumulator for the given nominal conversion rate.
+  pub const fn new(rate: ConversionRate, mode: ConversionMode) -> Self {
+    Self { rate, mode, offset: None, octaves: [Octave::new(); OCTAVES] }
+  }
+
+  /// Add a conversion result.
+  pub fn push(&mut self, value: i32) {
+    // Subtract the first value to keep the averages small.
+    let offset = *self.offset.get_or_insert(value);
+    let mut average = f64::from(value) - f64::from(offset);
+
+    for octave in &mut self.octaves {
+      if let Some(prev) = octave.prev {
+        octave.sum += (average - prev) * (average - prev);
+        octave.count += 1;
+      }
+      octave.prev = Some(average);
+
+      match octave.pending.take() {
+        Some(pending) => average = (pending + average) / 2.0,
+        None => {
+          octave.pending = Some(average);
+          break
+        },
+      }
+    }
+  }
+
+  /// Get the Allan deviation for the averaging factor `2^octave`, if enough results were added.
+  pub fn point(&self, octave: usize) -> Option<Point> {
+    let Octave { sum, count, .. } = *self.octaves.get(octave)?;
+    if count == 0 {
+      return None
+    }
+
+    let m = 1 << octave;
+    let deviation = libm::sqrt(sum / count as f64 / 2.0) as f32;
+    Some(Point { m, tau: tau(self.rate, self.mode, m), deviation, count })
+  }
+
+  /// Get the Allan deviations for all octaves with enough results.
+  pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+    (0..OCTAVES).map_while(|octave| self.point(octave))
+  }
+}
+
+/// Streaming overlapping Allan deviation using a history of `N` results.
+///
+/// The averaging factors `m = 1, 2, 4, …` below `N / 2` are evaluated over all overlapping windows. Only the last
+/// `N` results and two window sums per averaging factor are kept. The window sums are updated exactly using integer
+/// arithmetic, so they do not lose precision on long series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlappingAllan<const N: usize> {
+  rate: ConversionRate,
+  mode: ConversionMode,
+  history: [i32; N],
+  len: u64,
+  windows: [(i64, i64); u32::BITS as usize],
+  sums: [f64; u32::BITS as usize],
+  counts: [u64; u32::BITS as usize],
+}
+
+impl<const N: usize> OverlappingAllan<N> {
+  /// Create an empty accumulator for the given nominal conversion rate.
+  pub const fn new(rate: ConversionRate, mode: ConversionMode) -> Self {
+    Self {
+      rate,
+      mode,
+      history: [0; N],
+      len: 0,
+      windows: [(0, 0); u32::BITS as usize],
+      sums: [0.0; u32::BITS as usize],
+      counts: [0; u32::BITS as usize],
+    }
+  }
+
+  /// Number of octaves which fit into the history.
+  const fn octaves() -> usize {
+    let mut octaves = 0;
+    while octaves < u32::BITS as usize && 2 << octaves < N {
+      octaves += 1;
+    }
+    octaves
+  }
+
+  /// Add a conversion result.
+  pub fn push(&mut self, value: i32) {
+    if N == 0 {
+      return
+    }
+
+    let t = self.len;
+    self.history[(t % N as u64) as usize] = value;
+    self.len += 1;
+
+    // Results before the first one are zero, they are not stored.
+    let at = |i: u64| if i <= t { i64::from(self.history[((t - i) % N as u64) as usize]) } else { 0 };
+
+    for octave in 0..Self::octaves() {
+      let m = 1u64 << octave;
+
+      // Sums over the windows `t - m + 1..=t` and `t - 2m + 1..=t - m`.
+      let (recent, older) = &mut self.windows[octave];
+      *recent += at(0) - at(m);
+      *older += at(m) - at(2 * m);
+
+      if t + 1 >= 2 * m {
+        let d = (*recent - *older) as f64;
+        self.sums[octave] += d * d;
+        self.counts[octave] += 1;
+      }
+    }
+  }
+
+  /// Get the overlapping Allan deviation for the averaging factor `2^octave`, if enough results were added.
+  pub fn point(&self, octave: usize) -> Option<Point> {
+    if octave >= Self::octaves() || self.counts[octave] == 0 {
+      return None
+    }
+
+    let m = 1u32 << octave;
+    let count = self.counts[octave];
+    let variance = self.sums[octave] / count as f64 / (2.0 * f64::from(m) * f64::from(m));
+    Some(Point { m, tau: tau(self.rate, self.mode, m), deviation: libm::sqrt(variance) as f32, count })
+  }
+
+  /// Get the overlapping Allan deviations for all octaves with enough results.
+  pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+    (0..Self::octaves()).map_while(|octave| self.point(octave))
+  }
+}
@@ -0,0 +1,218 @@
+use embedded_hal::spi::SpiDevice;
+
+use crate::{Calibration, Conversion, ConversionRate, DataReady, Error, Max11214, Pga, Range, Sample};
+
+/// Calibration performed after the PGA gain was changed.
+///
+/// This is called while the conversion is paused, after the new gain has been written.
+pub trait GainCalibration {
+  /// Calibrate the ADC for the given gain and conversion rate.
+  fn calibrate<SPI, const N: usize>(
+    &mut self,
+    adc: &mut Max11214<SPI, Conversion, N>,
+    pga: Pga,
+    rate: ConversionRate,
+  ) -> Result<(), Error<SPI::Error>>
+  where
+    SPI: SpiDevice<u8>;
+
+  /// Check if no calibration is performed, so the conversion does not need to be paused for it.
+  fn is_noop(&self) -> bool {
+    false
+  }
+}
+
+/// Do not calibrate after changing the gain.
+impl GainCalibration for () {
+  fn calibrate<SPI, const N: usize>(
+    &mut self,
+    _: &mut Max11214<SPI, Conversion, N>,
+    _: Pga,
+    _: ConversionRate,
+  ) -> Result<(), Error<SPI::Error>>
+  where
+    SPI: SpiDevice<u8>,
+  {
+    Ok(())
+  }
+
+  fn is_noop(&self) -> bool {
+    true
+  }
+}
+
+/// Run a self-calibration after changing the gain.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SelfCalibrate;
+
+impl GainCalibration for SelfCalibrate {
+  fn calibrate<SPI, const N: usize>(
+    &mut self,
+    adc: &mut Max11214<SPI, Conversion, N>,
+    _: Pga,
+    rate: ConversionRate,
+  ) -> Result<(), Error<SPI::Error>>
+  where
+    SPI: SpiDevice<u8>,
+  {
+    adc.calibrate(Calibration::SelfCalibration, rate)
+  }
+}
+
+/// A conversion result together with the PGA gain it was taken at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangedSample {
+  /// The conversion result.
+  pub sample: Sample,
+  /// The PGA gain.
+  pub pga: Pga,
+}
+
+/// Automatic PGA gain selection.
+///
+/// The gain is lowered immediately when a result exceeds the upper threshold or is flagged as overranged, and raised
+/// when [`hold`](AutoRange::hold) consecutive results are below the lower threshold. The lower threshold should be
+/// less than half the upper threshold, so a result does not exceed the upper threshold after doubling the gain.
+///
+/// Only the PGA gain is adjusted, the modulator digital gain is left unchanged. Since conversion results include the
+/// digital gain, the thresholds apply to the digitally amplified results.
+#[derive(Debug, Clone)]
+pub struct AutoRange<C> {
+  min: Pga,
+  max: Pga,
+  lower: f32,
+  upper: f32,
+  hold: u16,
+  below: u16,
+  current: Option<Pga>,
+  calibration: C,
+}
+
+impl AutoRange<()> {
+  /// Create an automatic gain selection using all gains, a lower threshold of 40 % and an upper threshold of 90 % of
+  /// full scale, holding for 8 results and without calibration.
+  pub const fn new() -> Self {
+    Self { min: Pga::X1, max: Pga::X128, lower: 0.4, upper: 0.9, hold: 8, below: 0, current: None, calibration: () }
+  }
+}
+
+impl Default for AutoRange<()> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<C> AutoRange<C> {
+  /// Set the range of gains to choose from.
+  ///
+  /// The gains are swapped if `min` is higher than `max`.
+  pub const fn gains(mut self, min: Pga, max: Pga) -> Self {
+    (self.min, self.max) = if min as u8 > max as u8 { (max, min) } else { (min, max) };
+    self
+  }
+
+  /// Set the lower and upper thresholds as fractions of full scale.
+  pub const fn thresholds(mut self, lower: f32, upper: f32) -> Self {
+    self.lower = lower;
+    self.upper = upper;
+    self
+  }
+
+  /// Set the number of consecutive results below the lower threshold before raising the gain.
+  pub const fn hold(mut self, hold: u16) -> Self {
+    self.hold = hold;
+    self
+  }
+
+  /// Set the calibration performed after changing the gain.
+  ///
+  /// Coefficients from a calibration table held by the driver are loaded when the conversion is restarted, without
+  /// any calibration set here.
+  pub fn calibration<C2>(self, calibration: C2) -> AutoRange<C2> {
+    let Self { min, max, lower, upper, hold, below, current, .. } = self;
+    AutoRange { min, max, lower, upper, hold, below, current, calibration }
+  }
+
+  /// Get the current gain, if known.
+  pub const fn current(&self) -> Option<Pga> {
+    self.current
+  }
+
+  /// Decide on the gain for the next results based on the given result.
+  fn next_gain(&mut self, sample: &Sample, range: Range, pga: Pga) -> Option<Pga> {
+    let full_scale = match range {
+      Range::Bipolar => 0x800000 as f32,
+      Range::Unipolar => 0x1000000 as f32,
+    };
+    let level = sample.value().unsigned_abs() as f32 / full_scale;
+
+    if sample.is_clipped() || sample.is_analog_overrange() || level >= self.upper {
+      self.below = 0;
+      return pga.lower().filter(|&pga| pga >= self.min)
+    }
+
+    if level >= self.lower {
+      self.below = 0;
+      return None
+    }
+
+    self.below = self.below.saturating_add(1);
+    if self.below < self.hold {
+      return None
+    }
+
+    self.below = 0;
+    pga.higher().filter(|&pga| pga <= self.max)
+  }
+}
+
+impl<SPI, E, const N: usize> Max11214<SPI, Conversion, N>
+where
+  SPI: SpiDevice<u8, Error = E>,
+{
+  /// Read the next conversion result and adjust the PGA gain if needed.
+  ///
+  /// If the PGA is disabled, it is enabled with the lowest allowed gain before reading. On the first call, the
+  /// calibration is run even if the gain is already set, unless it is a no-op. When the gain is changed, unsettled
+  /// results are discarded.
+  pub fn read_autoranged<R, C>(&mut self, ready: &mut R, auto: &mut AutoRange<C>) -> Result<RangedSample, Error<E>>
+  where
+    R: DataReady,
+    C: GainCalibration,
+  {
+    let pga = match auto.current {
+      Some(pga) => pga,
+      None => {
+        let old_pga = self.pga()?;
+        let pga = old_pga.unwrap_or(auto.min).clamp(auto.min, auto.max);
+
+        if old_pga != Some(pga) {
+          self.change_gain(pga, &mut auto.calibration)?;
+        } else if !auto.calibration.is_noop() {
+          let rate = self.conversion.rate;
+          self.pause_with(|adc| auto.calibration.calibrate(adc, pga, rate))?;
+        }
+
+        auto.current = Some(pga);
+        pga
+      },
+    };
+
+    let sample = self.wait_sample(ready)?;
+
+    if let Some(next) = auto.next_gain(&sample, self.conversion.range, pga) {
+      self.change_gain(next, &mut auto.calibration)?;
+      auto.current = Some(next);
+    }
+
+    Ok(RangedSample { sample, pga })
+  }
+
+  fn change_gain<C>(&mut self, pga: Pga, calibration: &mut C) -> Result<(), Error<E>>
+  where
+    C: GainCalibration,
+  {
+    let rate = self.conversion.rate;
+    self.reconfigure_with(|config| config.pga = Some(pga), |adc, _| calibration.calibrate(adc, pga, rate))
+  }
+}
@@ -0,0 +1,44 @@
+use crate::register::{Ctrl1, Ctrl5, ReadReg};
+
+const FIRST: u8 = <Ctrl1 as ReadReg<u8>>::ADDR;
+const LAST: u8 = <Ctrl5 as ReadReg<u8>>::ADDR;
+
+/// Shadow copy of the `CTRL1` to `CTRL5` registers.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Cache {
+  enabled: bool,
+  regs: [Option<u8>; (LAST - FIRST + 1) as usize],
+}
+
+impl Cache {
+  pub const fn new() -> Self {
+    Self { enabled: false, regs: [None; (LAST - FIRST + 1) as usize] }
+  }
+
+  pub const fn is_enabled(&self) -> bool {
+    self.enabled
+  }
+
+  pub fn set_enabled(&mut self, enabled: bool) {
+    self.enabled = enabled;
+    self.invalidate();
+  }
+
+  pub fn invalidate(&mut self) {
+    self.regs = [None; (LAST - FIRST + 1) as usize];
+  }
+
+  pub fn get(&self, addr: u8) -> Option<u8> {
+    if !self.enabled || !(FIRST..=LAST).contains(&addr) {
+      return None
+    }
+
+    self.regs[usize::from(addr - FIRST)]
+  }
+
+  pub fn set(&mut self, addr: u8, value: u8) {
+    if self.enabled && (FIRST..=LAST).contains(&addr) {
+      self.regs[usize::from(addr - FIRST)] = Some(value);
+    }
+  }
+}
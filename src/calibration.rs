@@ -0,0 +1,105 @@
+use crate::{ConversionRate, Pga};
+
+/// Calibration coefficients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CalibrationData {
+  /// System offset calibration coefficient.
+  pub system_offset: u32,
+  /// System gain calibration coefficient.
+  pub system_gain: u32,
+  /// Self-calibration offset coefficient.
+  pub self_offset: u32,
+  /// Self-calibration gain coefficient.
+  pub self_gain: u32,
+}
+
+impl CalibrationData {
+  /// Size of the serialized representation in bytes.
+  pub const BYTES: usize = 16;
+
+  /// Serialize the calibration coefficients.
+  pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+    let mut bytes = [0; Self::BYTES];
+
+    for (chunk, value) in
+      bytes.chunks_exact_mut(4).zip([self.system_offset, self.system_gain, self.self_offset, self.self_gain])
+    {
+      chunk.copy_from_slice(&value.to_be_bytes());
+    }
+
+    bytes
+  }
+
+  /// Deserialize calibration coefficients.
+  pub fn from_bytes(bytes: &[u8; Self::BYTES]) -> Self {
+    let mut values = [0; 4];
+
+    for (value, chunk) in values.iter_mut().zip(bytes.chunks_exact(4)) {
+      *value = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    let [system_offset, system_gain, self_offset, self_gain] = values;
+    Self { system_offset, system_gain, self_offset, self_gain }
+  }
+}
+
+/// Calibration coefficients for a combination of PGA gain and conversion rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalibrationEntry {
+  /// PGA gain, or `None` if the PGA is disabled.
+  pub pga: Option<Pga>,
+  /// Conversion rate.
+  pub rate: ConversionRate,
+  /// Calibration coefficients.
+  pub data: CalibrationData,
+}
+
+/// A table of calibration coefficients for up to `N` combinations of PGA gain and conversion rate.
+///
+/// A self-calibration is only valid for the PGA gain and conversion rate it was run at. The table is held by the
+/// driver using [`Max11214::with_calibration_table`](crate::Max11214::with_calibration_table) and filled using
+/// `calibrate_table`. The matching coefficients are then loaded automatically whenever a conversion is started,
+/// including after changing the gain using [`AutoRange`](crate::AutoRange).
+#[derive(Debug, Clone)]
+pub struct CalibrationTable<const N: usize> {
+  entries: [Option<CalibrationEntry>; N],
+}
+
+impl<const N: usize> CalibrationTable<N> {
+  /// Create an empty table.
+  pub const fn new() -> Self {
+    Self { entries: [None; N] }
+  }
+
+  /// Get the calibration coefficients for a combination of PGA gain and conversion rate.
+  pub fn get(&self, pga: Option<Pga>, rate: ConversionRate) -> Option<&CalibrationData> {
+    self.entries.iter().flatten().find(|entry| entry.pga == pga && entry.rate == rate).map(|entry| &entry.data)
+  }
+
+  /// Insert or replace the calibration coefficients for a combination of PGA gain and conversion rate.
+  ///
+  /// Returns `false` if the table is full.
+  pub fn insert(&mut self, pga: Option<Pga>, rate: ConversionRate, data: CalibrationData) -> bool {
+    let slot = match self.entries.iter().position(|e| matches!(e, Some(e) if e.pga == pga && e.rate == rate)) {
+      Some(i) => &mut self.entries[i],
+      None => match self.entries.iter_mut().find(|e| e.is_none()) {
+        Some(slot) => slot,
+        None => return false,
+      },
+    };
+
+    *slot = Some(CalibrationEntry { pga, rate, data });
+    true
+  }
+
+  /// Iterate over all entries.
+  pub fn iter(&self) -> impl Iterator<Item = &CalibrationEntry> {
+    self.entries.iter().flatten()
+  }
+}
+
+impl<const N: usize> Default for CalibrationTable<N> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,158 @@
+//! Triggered capture of conversion results.
+
+use crate::{register::Stat, Format, Range, Sample, Status};
+
+/// Condition which starts a [`Capture`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+  /// The result is at or above the level.
+  Above(i32),
+  /// The result is at or below the level.
+  Below(i32),
+  /// The result crosses the level upwards.
+  Rising(i32),
+  /// The result crosses the level downwards.
+  Falling(i32),
+  /// The result is inside the window `low..=high`.
+  Inside(i32, i32),
+  /// The result is outside of the window `low..=high`.
+  Outside(i32, i32),
+  /// The analog input exceeded the overrange limit.
+  AnalogOverrange,
+  /// The conversion result was clipped.
+  DataOverrange,
+}
+
+impl Trigger {
+  fn is_triggered(self, prev: Option<i32>, sample: &Sample) -> bool {
+    let value = sample.value();
+
+    match self {
+      Self::Above(level) => value >= level,
+      Self::Below(level) => value <= level,
+      Self::Rising(level) => prev.is_some_and(|prev| prev < level) && value >= level,
+      Self::Falling(level) => prev.is_some_and(|prev| prev > level) && value <= level,
+      Self::Inside(low, high) => (low..=high).contains(&value),
+      Self::Outside(low, high) => !(low..=high).contains(&value),
+      Self::AnalogOverrange => sample.is_analog_overrange(),
+      Self::DataOverrange => sample.is_clipped(),
+    }
+  }
+}
+
+/// Placeholder for unused record entries.
+const EMPTY: Sample = Sample::new(0, Range::Bipolar, Format::TwosComplement, Status { status: Stat::empty() }, false);
+
+/// Triggered capture of `N` consecutive conversion results, including results before the trigger.
+///
+/// Results are kept in a ring buffer until the trigger condition is met, then the remaining results are collected.
+/// The trigger is only armed once enough results for the pre-trigger part were collected. Collided results are
+/// recorded, but never trigger the capture.
+#[derive(Debug, Clone)]
+pub struct Capture<const N: usize> {
+  trigger: Trigger,
+  pre_trigger: usize,
+  buf: [Sample; N],
+  next: usize,
+  len: usize,
+  remaining: Option<usize>,
+  prev: Option<i32>,
+}
+
+impl<const N: usize> Capture<N> {
+  /// Create a capture with the given trigger condition and number of results before the trigger.
+  ///
+  /// The number of results before the trigger is limited to `N - 1`.
+  pub const fn new(trigger: Trigger, pre_trigger: usize) -> Self {
+    let pre_trigger = if pre_trigger >= N { N.saturating_sub(1) } else { pre_trigger };
+    Self { trigger, pre_trigger, buf: [EMPTY; N], next: 0, len: 0, remaining: None, prev: None }
+  }
+
+  /// Get the trigger condition.
+  pub const fn trigger(&self) -> Trigger {
+    self.trigger
+  }
+
+  /// Check if the trigger condition was met and the capture is collecting the remaining results.
+  pub const fn is_triggered(&self) -> bool {
+    self.remaining.is_some()
+  }
+
+  /// Discard all collected results and re-arm the trigger.
+  pub fn reset(&mut self) {
+    self.next = 0;
+    self.len = 0;
+    self.remaining = None;
+    self.prev = None;
+  }
+
+  /// Add a conversion result.
+  ///
+  /// Results which are not fresh are ignored. Returns the record once it is complete, after which the capture is
+  /// re-armed.
+  pub fn push(&mut self, sample: Sample) -> Option<Record<N>> {
+    if N == 0 || !sample.is_fresh() {
+      return None
+    }
+
+    self.buf[self.next] = sample;
+    self.next = (self.next + 1) % N;
+    self.len = (self.len + 1).min(N);
+
+    let remaining = match self.remaining {
+      Some(remaining) => remaining - 1,
+      None if sample.is_collided() => return None,
+      None => {
+        let armed = self.len > self.pre_trigger;
+        let triggered = armed && self.trigger.is_triggered(self.prev, &sample);
+        self.prev = Some(sample.value());
+
+        if !triggered {
+          return None
+        }
+
+        N - self.pre_trigger - 1
+      },
+    };
+    self.remaining = Some(remaining);
+
+    if remaining > 0 {
+      return None
+    }
+
+    let mut samples = self.buf;
+    samples.rotate_left(self.next);
+    self.reset();
+
+    Some(Record { samples, trigger_index: self.pre_trigger })
+  }
+}
+
+/// A completed capture.
+#[derive(Debug, Clone)]
+pub struct Record<const N: usize> {
+  samples: [Sample; N],
+  trigger_index: usize,
+}
+
+impl<const N: usize> Record<N> {
+  /// Get the captured results, oldest first.
+  pub const fn samples(&self) -> &[Sample; N] {
+    &self.samples
+  }
+
+  /// Get the captured values, oldest first.
+  pub fn values(&self) -> impl Iterator<Item = i32> + '_ {
+    self.samples.iter().map(Sample::value)
+  }
+
+  /// Get the index of the result which met the trigger condition.
+  pub const fn trigger_index(&self) -> usize {
+    self.trigger_index
+  }
+
+  /// Get the result which met the trigger condition.
+  pub const fn trigger_sample(&self) -> &Sample {
+    &self.samples[self.trigger_index]
+  }
+}
@@ -36,8 +36,8 @@ impl Command {
     Self::new().union(Self::from_bits_truncate(rate as u8))
   }
 
-  pub const fn calibrate() -> Self {
-    Self::new().union(Self::CAL)
+  pub const fn calibrate(rate: ConversionRate) -> Self {
+    Self::new().union(Self::CAL).union(Self::from_bits_truncate(rate as u8))
   }
 
   pub const fn power_down() -> Self {
@@ -0,0 +1,253 @@
+use crate::{register::*, ClockSource, DigitalGain, Filter, FirPhase, Format, Gpio, Pga, Range, SyncMode};
+
+/// ADC configuration.
+///
+/// Covers all fields of the `CTRL1` to `CTRL5` registers, except for the power-down and single-cycle bits, which
+/// are controlled by mode transitions, and the calibration selection bits, which are controlled by
+/// `self_calibrate`.
+///
+/// ```rust
+/// use max112x::{ClockSource, Config, Filter, Pga};
+///
+/// const CONFIG: Config = Config::new().clock(ClockSource::External).pga(Some(Pga::X4)).filter(Filter::Fir);
+/// # assert_eq!(CONFIG.pga, Some(Pga::X4));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+  /// System clock source.
+  pub clock: ClockSource,
+  /// Synchronization mode.
+  pub sync: SyncMode,
+  /// Input range.
+  pub range: Range,
+  /// Bipolar range format.
+  pub format: Format,
+  /// Continuously repeat single-cycle conversions.
+  pub continuous_single_cycle: bool,
+  /// Modulator digital gain.
+  pub digital_gain: DigitalGain,
+  /// Enable the analog input buffers.
+  pub input_buffer: bool,
+  /// Use the PGA in low power mode.
+  pub pga_low_power: bool,
+  /// PGA gain, or `None` if the PGA is disabled.
+  pub pga: Option<Pga>,
+  /// Enable the modulator sync pulse.
+  pub modulator_sync: bool,
+  /// Enable the modulator output mode.
+  pub modulator_output: bool,
+  /// Use 32-bit data output.
+  pub data32: bool,
+  /// Digital filter.
+  pub filter: Filter,
+  /// FIR filter phase response.
+  pub fir_phase: FirPhase,
+  /// Configuration of the `GPIO1` to `GPIO3` pins.
+  pub gpio: [Gpio; 3],
+  /// Apply the self-calibration offset coefficient.
+  pub self_offset_calibration: bool,
+  /// Apply the self-calibration gain coefficient.
+  pub self_gain_calibration: bool,
+  /// Apply the system offset calibration coefficient.
+  pub system_offset_calibration: bool,
+  /// Apply the system gain calibration coefficient.
+  pub system_gain_calibration: bool,
+}
+
+impl Config {
+  /// Create a configuration with the default settings.
+  ///
+  /// This uses the internal clock, pulse synchronization, bipolar two's complement format, the SINC filter,
+  /// no PGA and only the self-calibration coefficients.
+  pub const fn new() -> Self {
+    Self {
+      clock: ClockSource::Internal,
+      sync: SyncMode::Pulse,
+      range: Range::Bipolar,
+      format: Format::TwosComplement,
+      continuous_single_cycle: false,
+      digital_gain: DigitalGain::X1,
+      input_buffer: false,
+      pga_low_power: false,
+      pga: None,
+      modulator_sync: false,
+      modulator_output: false,
+      data32: false,
+      filter: Filter::Sinc,
+      fir_phase: FirPhase::Linear,
+      gpio: [Gpio::Input; 3],
+      self_offset_calibration: true,
+      self_gain_calibration: true,
+      system_offset_calibration: false,
+      system_gain_calibration: false,
+    }
+  }
+
+  /// Set the system clock source.
+  pub const fn clock(mut self, clock: ClockSource) -> Self {
+    self.clock = clock;
+    self
+  }
+
+  /// Set the synchronization mode.
+  pub const fn sync(mut self, sync: SyncMode) -> Self {
+    self.sync = sync;
+    self
+  }
+
+  /// Set the input range.
+  pub const fn range(mut self, range: Range) -> Self {
+    self.range = range;
+    self
+  }
+
+  /// Set the bipolar range format.
+  pub const fn format(mut self, format: Format) -> Self {
+    self.format = format;
+    self
+  }
+
+  /// Set whether single-cycle conversions are repeated continuously.
+  pub const fn continuous_single_cycle(mut self, enable: bool) -> Self {
+    self.continuous_single_cycle = enable;
+    self
+  }
+
+  /// Set the modulator digital gain.
+  pub const fn digital_gain(mut self, gain: DigitalGain) -> Self {
+    self.digital_gain = gain;
+    self
+  }
+
+  /// Enable or disable the analog input buffers.
+  pub const fn input_buffer(mut self, enable: bool) -> Self {
+    self.input_buffer = enable;
+    self
+  }
+
+  /// Enable or disable the PGA low power mode.
+  pub const fn pga_low_power(mut self, enable: bool) -> Self {
+    self.pga_low_power = enable;
+    self
+  }
+
+  /// Set the PGA gain.
+  pub const fn pga(mut self, pga: Option<Pga>) -> Self {
+    self.pga = pga;
+    self
+  }
+
+  /// Enable or disable the modulator sync pulse.
+  pub const fn modulator_sync(mut self, enable: bool) -> Self {
+    self.modulator_sync = enable;
+    self
+  }
+
+  /// Enable or disable the modulator output mode.
+  pub const fn modulator_output(mut self, enable: bool) -> Self {
+    self.modulator_output = enable;
+    self
+  }
+
+  /// Enable or disable 32-bit data output.
+  pub const fn data32(mut self, enable: bool) -> Self {
+    self.data32 = enable;
+    self
+  }
+
+  /// Set the digital filter.
+  pub const fn filter(mut self, filter: Filter) -> Self {
+    self.filter = filter;
+    self
+  }
+
+  /// Set the FIR filter phase response.
+  pub const fn fir_phase(mut self, phase: FirPhase) -> Self {
+    self.fir_phase = phase;
+    self
+  }
+
+  /// Set the configuration of the `GPIO1` to `GPIO3` pins.
+  pub const fn gpio(mut self, gpio: [Gpio; 3]) -> Self {
+    self.gpio = gpio;
+    self
+  }
+
+  /// Set which self-calibration coefficients are applied.
+  pub const fn self_calibration(mut self, offset: bool, gain: bool) -> Self {
+    self.self_offset_calibration = offset;
+    self.self_gain_calibration = gain;
+    self
+  }
+
+  /// Set which system calibration coefficients are applied.
+  pub const fn system_calibration(mut self, offset: bool, gain: bool) -> Self {
+    self.system_offset_calibration = offset;
+    self.system_gain_calibration = gain;
+    self
+  }
+
+  pub(crate) fn from_regs(ctrl1: Ctrl1, ctrl2: Ctrl2, ctrl3: Ctrl3, ctrl4: Ctrl4, ctrl5: Ctrl5) -> Self {
+    Self {
+      clock: ctrl1.clock(),
+      sync: ctrl1.sync(),
+      range: ctrl1.range(),
+      format: ctrl1.format(),
+      continuous_single_cycle: ctrl1.contains(Ctrl1::CONTSC),
+      digital_gain: ctrl2.digital_gain(),
+      input_buffer: ctrl2.contains(Ctrl2::BUFEN),
+      pga_low_power: ctrl2.contains(Ctrl2::LPMODE),
+      pga: ctrl2.pga(),
+      modulator_sync: ctrl3.contains(Ctrl3::ENMSYNC),
+      modulator_output: ctrl3.contains(Ctrl3::MODBITS),
+      data32: ctrl3.contains(Ctrl3::DATA32),
+      filter: ctrl3.filter(),
+      fir_phase: ctrl3.fir_phase(),
+      gpio: ctrl4.gpio(),
+      self_offset_calibration: !ctrl5.contains(Ctrl5::NOSCO),
+      self_gain_calibration: !ctrl5.contains(Ctrl5::NOSCG),
+      system_offset_calibration: !ctrl5.contains(Ctrl5::NOSYSO),
+      system_gain_calibration: !ctrl5.contains(Ctrl5::NOSYSG),
+    }
+  }
+
+  pub(crate) fn ctrl1(&self, ctrl1: Ctrl1) -> Ctrl1 {
+    let mut ctrl1 = ctrl1.with_clock(self.clock).with_sync(self.sync).with_range(self.range).with_format(self.format);
+    ctrl1.set(Ctrl1::CONTSC, self.continuous_single_cycle);
+    ctrl1
+  }
+
+  pub(crate) fn ctrl2(&self, ctrl2: Ctrl2) -> Ctrl2 {
+    let mut ctrl2 = ctrl2.with_digital_gain(self.digital_gain).with_pga(self.pga);
+    ctrl2.set(Ctrl2::BUFEN, self.input_buffer);
+    ctrl2.set(Ctrl2::LPMODE, self.pga_low_power);
+    ctrl2
+  }
+
+  pub(crate) fn ctrl3(&self, ctrl3: Ctrl3) -> Ctrl3 {
+    let mut ctrl3 = ctrl3.with_filter(self.filter).with_fir_phase(self.fir_phase);
+    ctrl3.set(Ctrl3::ENMSYNC, self.modulator_sync);
+    ctrl3.set(Ctrl3::MODBITS, self.modulator_output);
+    ctrl3.set(Ctrl3::DATA32, self.data32);
+    ctrl3
+  }
+
+  pub(crate) fn ctrl4(&self, ctrl4: Ctrl4) -> Ctrl4 {
+    ctrl4.with_gpio(self.gpio)
+  }
+
+  pub(crate) fn ctrl5(&self, ctrl5: Ctrl5) -> Ctrl5 {
+    let mut ctrl5 = ctrl5;
+    ctrl5.set(Ctrl5::NOSCO, !self.self_offset_calibration);
+    ctrl5.set(Ctrl5::NOSCG, !self.self_gain_calibration);
+    ctrl5.set(Ctrl5::NOSYSO, !self.system_offset_calibration);
+    ctrl5.set(Ctrl5::NOSYSG, !self.system_gain_calibration);
+    ctrl5
+  }
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,230 @@
+use crate::{CalibrationData, Sample};
+
+/// Maximum number of polynomial coefficients.
+const MAX_TERMS: usize = 4;
+
+/// Number of fractional bits of the fixed-point coefficients.
+const FRAC_BITS: u32 = 32;
+
+/// Number of fractional bits of the normalized input.
+const INPUT_FRAC_BITS: u32 = 23;
+
+/// A software correction of conversion results into engineering units.
+///
+/// The correction is a polynomial `y = c0 + c1·x + c2·x² + c3·x³` of the normalized conversion result
+/// `x = value / 2²³`, evaluated in fixed point. The unit and resolution of `y` are chosen by the coefficients, e.g.
+/// microvolts or millidegrees.
+///
+/// ```rust
+/// use max112x::Correction;
+///
+/// // Two reference points: code 0 is 0 µV, code 0x400000 is 1,250,000 µV.
+/// let correction = Correction::fit_linear(&[(0, 0.0), (0x400000, 1_250_000.0)]).unwrap();
+/// assert_eq!(correction.apply(0x200000), 625_000);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Correction {
+  coefficients: [i64; MAX_TERMS],
+  terms: u8,
+}
+
+impl Correction {
+  /// Maximum number of polynomial coefficients.
+  pub const MAX_TERMS: usize = MAX_TERMS;
+
+  /// Size of the serialized representation in bytes.
+  pub const BYTES: usize = 1 + MAX_TERMS * 8;
+
+  /// A correction which returns its input unchanged.
+  pub const IDENTITY: Self = Self { coefficients: [0, 1 << (FRAC_BITS + INPUT_FRAC_BITS), 0, 0], terms: 2 };
+
+  /// Create a correction from raw fixed-point coefficients with 32 fractional bits, lowest order first.
+  ///
+  /// Returns `None` if more than [`Self::MAX_TERMS`] coefficients are given.
+  pub fn from_raw(raw: &[i64]) -> Option<Self> {
+    let mut coefficients = [0; MAX_TERMS];
+    coefficients.get_mut(..raw.len())?.copy_from_slice(raw);
+    Some(Self { coefficients, terms: raw.len() as u8 })
+  }
+
+  /// Create a correction from floating point coefficients of the normalized input, lowest order first.
+  ///
+  /// Returns `None` if more than [`Self::MAX_TERMS`] coefficients are given or a coefficient is not within
+  /// `-2^31..2^31`.
+  ///
+  /// ```rust
+  /// use max112x::Correction;
+  ///
+  /// assert!(Correction::from_coefficients(&[0.0, 1.0e9]).is_some());
+  /// assert!(Correction::from_coefficients(&[0.0, 1.0e10]).is_none());
+  /// assert!(Correction::from_coefficients(&[f64::NAN]).is_none());
+  /// ```
+  pub fn from_coefficients(coefficients: &[f64]) -> Option<Self> {
+    const LIMIT: f64 = (1u64 << 63) as f64;
+
+    let mut raw = [0; MAX_TERMS];
+    let raw = raw.get_mut(..coefficients.len())?;
+
+    for (raw, coefficient) in raw.iter_mut().zip(coefficients) {
+      let scaled = libm::round(coefficient * (1u64 << FRAC_BITS) as f64);
+      if !(-LIMIT..LIMIT).contains(&scaled) {
+        return None
+      }
+
+      *raw = scaled as i64;
+    }
+
+    Self::from_raw(raw)
+  }
+
+  /// Fit a straight line through the given `(value, reference)` points using least squares.
+  ///
+  /// Returns `None` if fewer than two distinct values are given or a coefficient is out of range.
+  pub fn fit_linear(points: &[(i32, f64)]) -> Option<Self> {
+    Self::fit_polynomial(points, 1)
+  }
+
+  /// Fit a polynomial of the given order through the given `(value, reference)` points using least squares.
+  ///
+  /// Returns `None` if the order is too high, there are not enough distinct points or a coefficient is out of range.
+  pub fn fit_polynomial(points: &[(i32, f64)], order: usize) -> Option<Self> {
+    let n = order + 1;
+    if n > MAX_TERMS || points.len() < n {
+      return None
+    }
+
+    // Normal equations `A·c = b` as an augmented matrix.
+    let mut m = [[0.0f64; MAX_TERMS + 1]; MAX_TERMS];
+    for &(value, reference) in points {
+      let x = f64::from(value) / f64::from(1u32 << INPUT_FRAC_BITS);
+
+      let mut powers = [1.0; MAX_TERMS * 2];
+      for i in 1..powers.len() {
+        powers[i] = powers[i - 1] * x;
+      }
+
+      for row in 0..n {
+        for col in 0..n {
+          m[row][col] += powers[row + col];
+        }
+        m[row][MAX_TERMS] += powers[row] * reference;
+      }
+    }
+
+    // Gaussian elimination with partial pivoting.
+    for col in 0..n {
+      let pivot = (col..n).max_by(|&a, &b| libm::fabs(m[a][col]).total_cmp(&libm::fabs(m[b][col])))?;
+      if libm::fabs(m[pivot][col]) < f64::EPSILON {
+        return None
+      }
+      m.swap(col, pivot);
+
+      let pivot_row = m[col];
+      for (row, values) in m[..n].iter_mut().enumerate() {
+        if row != col {
+          let factor = values[col] / pivot_row[col];
+          for (value, pivot) in values[col..].iter_mut().zip(&pivot_row[col..]) {
+            *value -= factor * pivot;
+          }
+        }
+      }
+    }
+
+    let mut coefficients = [0.0; MAX_TERMS];
+    for (i, coefficient) in coefficients[..n].iter_mut().enumerate() {
+      *coefficient = m[i][MAX_TERMS] / m[i][i];
+    }
+
+    Self::from_coefficients(&coefficients[..n])
+  }
+
+  /// Get the raw fixed-point coefficients with 32 fractional bits, lowest order first.
+  pub fn raw(&self) -> &[i64] {
+    &self.coefficients[..usize::from(self.terms)]
+  }
+
+  /// Apply the correction to a conversion result, rounding to the nearest integer.
+  pub fn apply(&self, value: i32) -> i32 {
+    let x = i128::from(value);
+
+    let y = self.raw().iter().rev().fold(0i128, |acc, &c| ((acc * x) >> INPUT_FRAC_BITS) + i128::from(c));
+    let y = (y + (1 << (FRAC_BITS - 1))) >> FRAC_BITS;
+
+    y.clamp(i32::MIN.into(), i32::MAX.into()) as i32
+  }
+
+  /// Apply the correction to a sample.
+  pub fn apply_sample(&self, sample: &Sample) -> i32 {
+    self.apply(sample.value())
+  }
+
+  /// Serialize the correction.
+  pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+    let mut bytes = [0; Self::BYTES];
+    bytes[0] = self.terms;
+
+    for (chunk, coefficient) in bytes[1..].chunks_exact_mut(8).zip(self.coefficients) {
+      chunk.copy_from_slice(&coefficient.to_be_bytes());
+    }
+
+    bytes
+  }
+
+  /// Deserialize a correction.
+  ///
+  /// Returns `None` if the data is invalid.
+  pub fn from_bytes(bytes: &[u8; Self::BYTES]) -> Option<Self> {
+    let terms = bytes[0];
+    if usize::from(terms) > MAX_TERMS {
+      return None
+    }
+
+    let mut coefficients = [0; MAX_TERMS];
+    for (coefficient, chunk) in coefficients.iter_mut().zip(bytes[1..].chunks_exact(8)) {
+      *coefficient = i64::from_be_bytes(chunk.try_into().ok()?);
+    }
+
+    Some(Self { coefficients, terms })
+  }
+}
+
+impl Default for Correction {
+  fn default() -> Self {
+    Self::IDENTITY
+  }
+}
+
+/// Hardware calibration coefficients together with a software correction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CalibrationRecord {
+  /// Hardware calibration coefficients.
+  pub hardware: CalibrationData,
+  /// Software correction.
+  pub correction: Correction,
+}
+
+impl CalibrationRecord {
+  /// Size of the serialized representation in bytes.
+  pub const BYTES: usize = CalibrationData::BYTES + Correction::BYTES;
+
+  /// Serialize the record.
+  pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+    let mut bytes = [0; Self::BYTES];
+    let (hardware, correction) = bytes.split_at_mut(CalibrationData::BYTES);
+    hardware.copy_from_slice(&self.hardware.to_bytes());
+    correction.copy_from_slice(&self.correction.to_bytes());
+    bytes
+  }
+
+  /// Deserialize a record.
+  ///
+  /// Returns `None` if the data is invalid.
+  pub fn from_bytes(bytes: &[u8; Self::BYTES]) -> Option<Self> {
+    let (hardware, correction) = bytes.split_at(CalibrationData::BYTES);
+
+    Some(Self {
+      hardware: CalibrationData::from_bytes(hardware.try_into().ok()?),
+      correction: Correction::from_bytes(correction.try_into().ok()?)?,
+    })
+  }
+}
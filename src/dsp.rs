@@ -0,0 +1,429 @@
+//! Digital filters applied to conversion results after reading them.
+//!
+//! Filters implement [`Stage`] and can be chained using [`Stage::then`]. They are applied to a sample iterator using
+//! [`SampleIteratorExt::filtered`], or to a sample stream using [`filter_stream`] with the `async` feature.
+//!
+//! ```rust
+//! use max112x::dsp::{Boxcar, Iir, Median, Stage};
+//!
+//! let mut stage = Median::<3>::new().then(Boxcar::<2>::new());
+//!
+//! let output = [10, 12, 1000, 14, 16].into_iter().filter_map(|v| stage.process(v)).collect::<Vec<_>>();
+//! assert_eq!(output, [13, 15]);
+//!
+//! // Decimating stages lower the output rate.
+//! assert_eq!(Iir::new(3).then(max112x::dsp::Cic::new(2, 8)).decimation(), 8);
+//! ```
+
+use crate::{Hertz, Sample};
+
+/// A digital filter stage processing conversion results.
+pub trait Stage {
+  /// Process a conversion result, returning an output value if one is produced.
+  fn process(&mut self, value: i32) -> Option<i32>;
+
+  /// Clear the filter state.
+  fn reset(&mut self);
+
+  /// Get the number of input values per output value.
+  fn decimation(&self) -> u32 {
+    1
+  }
+
+  /// Get the output rate for the given input rate.
+  fn output_rate(&self, input: Hertz) -> Hertz {
+    Hertz::new(input.num, input.den * u64::from(self.decimation()))
+  }
+
+  /// Process a sample.
+  ///
+  /// Samples which are not fresh or which collided are dropped. The output sample keeps the status of the input
+  /// sample which produced it.
+  fn process_sample(&mut self, sample: Sample) -> Option<Sample> {
+    if !sample.is_fresh() || sample.is_collided() {
+      return None
+    }
+
+    self.process(sample.value()).map(|value| sample.with_value(value))
+  }
+
+  /// Feed the output of this stage into another stage.
+  fn then<S>(self, next: S) -> Chain<Self, S>
+  where
+    Self: Sized,
+    S: Stage,
+  {
+    Chain { first: self, second: next }
+  }
+}
+
+/// Two stages applied one after the other.
+///
+/// Created by [`Stage::then`].
+#[derive(Debug, Clone)]
+pub struct Chain<A, B> {
+  first: A,
+  second: B,
+}
+
+impl<A, B> Stage for Chain<A, B>
+where
+  A: Stage,
+  B: Stage,
+{
+  fn process(&mut self, value: i32) -> Option<i32> {
+    self.first.process(value).and_then(|value| self.second.process(value))
+  }
+
+  fn reset(&mut self) {
+    self.first.reset();
+    self.second.reset();
+  }
+
+  fn decimation(&self) -> u32 {
+    self.first.decimation() * self.second.decimation()
+  }
+}
+
+/// Round the quotient to the nearest integer, away from zero on ties.
+fn div_round(num: i128, den: i128) -> i32 {
+  let half = den / 2;
+  (if num < 0 { (num - half) / den } else { (num + half) / den }) as i32
+}
+
+/// Moving average over the last `N` values.
+///
+/// Produces an output for every input once `N` values have been processed.
+#[derive(Debug, Clone)]
+pub struct Boxcar<const N: usize> {
+  values: [i32; N],
+  len: usize,
+  next: usize,
+  sum: i64,
+}
+
+impl<const N: usize> Boxcar<N> {
+  /// Create a moving average.
+  pub const fn new() -> Self {
+    Self { values: [0; N], len: 0, next: 0, sum: 0 }
+  }
+}
+
+impl<const N: usize> Default for Boxcar<N> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<const N: usize> Stage for Boxcar<N> {
+  fn process(&mut self, value: i32) -> Option<i32> {
+    if N == 0 {
+      return Some(value)
+    }
+
+    if self.len == N {
+      self.sum -= i64::from(self.values[self.next]);
+    } else {
+      self.len += 1;
+    }
+
+    self.values[self.next] = value;
+    self.sum += i64::from(value);
+    self.next = (self.next + 1) % N;
+
+    (self.len == N).then(|| div_round(self.sum.into(), N as i128))
+  }
+
+  fn reset(&mut self) {
+    *self = Self::new();
+  }
+}
+
+/// Median of the last `N` values, rejecting isolated spikes.
+///
+/// Produces an output for every input once `N` values have been processed. `N` should be odd.
+#[derive(Debug, Clone)]
+pub struct Median<const N: usize> {
+  values: [i32; N],
+  len: usize,
+  next: usize,
+}
+
+impl<const N: usize> Median<N> {
+  /// Create a median filter.
+  pub const fn new() -> Self {
+    Self { values: [0; N], len: 0, next: 0 }
+  }
+}
+
+impl<const N: usize> Default for Median<N> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<const N: usize> Stage for Median<N> {
+  fn process(&mut self, value: i32) -> Option<i32> {
+    if N == 0 {
+      return Some(value)
+    }
+
+    self.values[self.next] = value;
+    self.next = (self.next + 1) % N;
+    self.len = (self.len + 1).min(N);
+
+    if self.len < N {
+      return None
+    }
+
+    let mut sorted = self.values;
+    sorted.sort_unstable();
+    Some(sorted[N / 2])
+  }
+
+  fn reset(&mut self) {
+    *self = Self::new();
+  }
+}
+
+/// First-order IIR lowpass filter `y += (x - y) / 2^shift`.
+///
+/// The filter state is initialized with the first value. The time constant is about `2^shift` conversion periods.
+#[derive(Debug, Clone)]
+pub struct Iir {
+  shift: u8,
+  state: Option<i64>,
+}
+
+impl Iir {
+  /// Create an IIR filter with a smoothing factor of `1 / 2^shift`.
+  ///
+  /// The shift is limited to 31.
+  pub const fn new(shift: u8) -> Self {
+    Self { shift: if shift > 31 { 31 } else { shift }, state: None }
+  }
+}
+
+impl Stage for Iir {
+  fn process(&mut self, value: i32) -> Option<i32> {
+    // The state is kept with `shift` fractional bits.
+    let x = i64::from(value) << self.shift;
+    let state = match self.state {
+      Some(state) => state + ((x - state) >> self.shift),
+      None => x,
+    };
+    self.state = Some(state);
+
+    Some(div_round(state.into(), 1 << self.shift))
+  }
+
+  fn reset(&mut self) {
+    self.state = None;
+  }
+}
+
+/// Average of consecutive blocks of values, producing one output per block.
+#[derive(Debug, Clone)]
+pub struct BlockAverage {
+  decimation: u32,
+  sum: i64,
+  count: u32,
+}
+
+impl BlockAverage {
+  /// Create a block average over `decimation` values, which is at least one.
+  pub const fn new(decimation: u32) -> Self {
+    Self { decimation: if decimation == 0 { 1 } else { decimation }, sum: 0, count: 0 }
+  }
+}
+
+impl Stage for BlockAverage {
+  fn process(&mut self, value: i32) -> Option<i32> {
+    self.sum += i64::from(value);
+    self.count += 1;
+
+    if self.count < self.decimation {
+      return None
+    }
+
+    let average = div_round(self.sum.into(), self.decimation.into());
+    self.sum = 0;
+    self.count = 0;
+    Some(average)
+  }
+
+  fn reset(&mut self) {
+    self.sum = 0;
+    self.count = 0;
+  }
+
+  fn decimation(&self) -> u32 {
+    self.decimation
+  }
+}
+
+/// Maximum order of a [`Cic`] decimator.
+pub const CIC_MAX_ORDER: usize = 4;
+
+/// Cascaded integrator-comb decimator with unity DC gain.
+///
+/// An order 1 CIC decimator is equivalent to a [`BlockAverage`]. Higher orders give a better stopband attenuation
+/// at the cost of a longer settling time of `order` output periods.
+///
+/// ```rust
+/// use max112x::dsp::{BlockAverage, Cic, Stage};
+///
+/// let mut cic = Cic::new(1, 4);
+/// let mut average = BlockAverage::new(4);
+/// for value in [3, -7, 12, 100, 5, 5, 6, 6] {
+///   assert_eq!(cic.process(value), average.process(value));
+/// }
+///
+/// // Large decimations are only limited for higher orders.
+/// assert_eq!(Cic::new(1, u32::MAX).decimation(), u32::MAX);
+/// assert_eq!(Cic::new(2, u32::MAX).decimation(), 1 << 20);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cic {
+  order: u8,
+  decimation: u32,
+  integrators: [i128; CIC_MAX_ORDER],
+  combs: [i128; CIC_MAX_ORDER],
+  count: u32,
+}
+
+impl Cic {
+  /// Create a CIC decimator.
+  ///
+  /// The order is limited to [`CIC_MAX_ORDER`] and the decimation to `2^(40 / order)`. Both are at least one. The
+  /// integrators and combs are 128 bits wide, so the output cannot overflow for any input.
+  pub const fn new(order: u8, decimation: u32) -> Self {
+    let order = if order == 0 {
+      1
+    } else if order as usize > CIC_MAX_ORDER {
+      CIC_MAX_ORDER as u8
+    } else {
+      order
+    };
+
+    let max_decimation = 1u64 << (40 / order as u32);
+    let decimation = if decimation == 0 {
+      1
+    } else if decimation as u64 > max_decimation {
+      max_decimation as u32
+    } else {
+      decimation
+    };
+
+    Self { order, decimation, integrators: [0; CIC_MAX_ORDER], combs: [0; CIC_MAX_ORDER], count: 0 }
+  }
+
+  fn gain(&self) -> i128 {
+    i128::from(self.decimation).pow(u32::from(self.order))
+  }
+}
+
+impl Stage for Cic {
+  fn process(&mut self, value: i32) -> Option<i32> {
+    let order = usize::from(self.order);
+
+    let mut acc = i128::from(value);
+    for integrator in &mut self.integrators[..order] {
+      *integrator = integrator.wrapping_add(acc);
+      acc = *integrator;
+    }
+
+    self.count += 1;
+    if self.count < self.decimation {
+      return None
+    }
+    self.count = 0;
+
+    for comb in &mut self.combs[..order] {
+      let prev = *comb;
+      *comb = acc;
+      acc = acc.wrapping_sub(prev);
+    }
+
+    Some(div_round(acc, self.gain()))
+  }
+
+  fn reset(&mut self) {
+    *self = Self::new(self.order, self.decimation);
+  }
+
+  fn decimation(&self) -> u32 {
+    self.decimation
+  }
+}
+
+/// Apply filter stages to an iterator over samples.
+pub trait SampleIteratorExt<E>: Iterator<Item = Result<Sample, E>> + Sized {
+  /// Apply a filter stage to the samples.
+  ///
+  /// Errors are passed through unchanged.
+  fn filtered<S>(self, stage: S) -> Filtered<Self, S>
+  where
+    S: Stage,
+  {
+    Filtered { samples: self, stage }
+  }
+}
+
+impl<I, E> SampleIteratorExt<E> for I where I: Iterator<Item = Result<Sample, E>> {}
+
+/// An iterator over filtered samples.
+///
+/// Created by [`SampleIteratorExt::filtered`].
+#[derive(Debug, Clone)]
+pub struct Filtered<I, S> {
+  samples: I,
+  stage: S,
+}
+
+impl<I, S> Filtered<I, S> {
+  /// Get the filter stage.
+  pub fn stage(&self) -> &S {
+    &self.stage
+  }
+}
+
+impl<I, S, E> Iterator for Filtered<I, S>
+where
+  I: Iterator<Item = Result<Sample, E>>,
+  S: Stage,
+{
+  type Item = Result<Sample, E>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      match self.samples.next()? {
+        Ok(sample) => {
+          if let Some(sample) = self.stage.process_sample(sample) {
+            return Some(Ok(sample))
+          }
+        },
+        Err(err) => return Some(Err(err)),
+      }
+    }
+  }
+}
+
+/// Apply a filter stage to a stream of samples.
+///
+/// Errors are passed through unchanged.
+#[cfg(feature = "async")]
+pub fn filter_stream<St, S, E>(stream: St, mut stage: S) -> impl futures_util::Stream<Item = Result<Sample, E>>
+where
+  St: futures_util::Stream<Item = Result<Sample, E>>,
+  S: Stage,
+{
+  use futures_util::StreamExt;
+
+  stream.filter_map(move |res| {
+    futures_util::future::ready(match res {
+      Ok(sample) => stage.process_sample(sample).map(Ok),
+      Err(err) => Some(Err(err)),
+    })
+  })
+}
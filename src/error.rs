@@ -3,4 +3,19 @@
 pub enum Error<SPI> {
   /// SPI error.
   Spi(SPI),
+  /// Data ready pin error.
+  Pin(embedded_hal::digital::ErrorKind),
+  /// The input was overranged during a system calibration, resulting in invalid coefficients.
+  CalibrationOverrange,
+  /// A system calibration resulted in a zero gain coefficient.
+  CalibrationZeroGain,
+  /// A register write could not be verified.
+  VerifyFailed {
+    /// Register address.
+    addr: u8,
+    /// Written value.
+    wrote: u8,
+    /// Value read back.
+    read: u8,
+  },
 }
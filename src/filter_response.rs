@@ -0,0 +1,190 @@
+//! Frequency response model of the digital filters.
+//!
+//! The model is based on the nominal conversion rates and idealized filter shapes:
+//!
+//! - The SINC filter is modeled as a 4th order SINC filter with notches at multiples of the continuous conversion
+//!   rate. In single-cycle mode, the filter has to settle within a single conversion, so the notches are at
+//!   multiples of four times the single-cycle conversion rate.
+//! - The FIR filter is modeled as flat up to 0.4 × the conversion rate, falling off to its stopband attenuation at
+//!   0.5 × the conversion rate, with an impulse response spanning [`FIR_LENGTH`] conversion periods.
+//!
+//! The IIR highpass filter only affects frequencies close to DC and is not modeled.
+//!
+//! ```rust
+//! use max112x::{filter_response, ConversionMode, ConversionRate, Filter};
+//!
+//! let (rate, filter, mode) = (ConversionRate::Hz62_5, Filter::Sinc, ConversionMode::Continuous);
+//!
+//! // The first notch is at the conversion rate.
+//! assert_eq!(filter_response::notches(rate, filter, mode).next(), Some(62.5));
+//! assert!(filter_response::magnitude_db(rate, filter, mode, 62.5) < -100.0);
+//!
+//! // The -3 dB bandwidth is well below the Nyquist frequency.
+//! let bandwidth = filter_response::bandwidth_3db(rate, filter, mode);
+//! assert!(bandwidth > 10.0 && bandwidth < 31.25);
+//! ```
+
+use core::f32::consts::PI;
+
+use crate::{ConversionMode, ConversionRate, Filter, FirPhase, SyncMode};
+
+/// Order of the SINC filter.
+pub const SINC_ORDER: u8 = 4;
+
+/// Modeled stopband attenuation of the FIR filter in decibels.
+pub const FIR_STOPBAND_DB: f32 = 100.0;
+
+/// Modeled length of the FIR filter impulse response in conversion periods.
+pub const FIR_LENGTH: u8 = 5;
+
+const FIR_PASSBAND_EDGE: f32 = 0.4;
+const FIR_STOPBAND_EDGE: f32 = 0.5;
+
+/// Check if the given filter can be used with the given conversion rate and mode.
+///
+/// The FIR filter is only available for some conversion rates in continuous mode.
+pub const fn is_supported(rate: ConversionRate, filter: Filter, mode: ConversionMode) -> bool {
+  match filter {
+    Filter::Sinc | Filter::SincHighpass => true,
+    Filter::Fir | Filter::FirHighpass => matches!(mode, ConversionMode::Continuous) && rate.supports_fir(),
+  }
+}
+
+/// Get the frequency of the first SINC filter notch in hertz.
+pub fn sinc_notch_hz(rate: ConversionRate, mode: ConversionMode) -> f32 {
+  match mode {
+    ConversionMode::Continuous => rate.hz(mode).to_f32(),
+    ConversionMode::SingleCycle => rate.hz(mode).to_f32() * f32::from(SINC_ORDER),
+  }
+}
+
+/// Get the magnitude response at the given frequency, relative to the response at DC.
+///
+/// In single-cycle mode, the SINC filter is always used.
+pub fn magnitude(rate: ConversionRate, filter: Filter, mode: ConversionMode, freq_hz: f32) -> f32 {
+  let freq_hz = libm::fabsf(freq_hz);
+
+  match (filter, mode) {
+    (Filter::Fir | Filter::FirHighpass, ConversionMode::Continuous) => {
+      let x = freq_hz / rate.hz(mode).to_f32();
+
+      if x <= FIR_PASSBAND_EDGE {
+        1.0
+      } else if x >= FIR_STOPBAND_EDGE {
+        from_db(-FIR_STOPBAND_DB)
+      } else {
+        from_db(-FIR_STOPBAND_DB * (x - FIR_PASSBAND_EDGE) / (FIR_STOPBAND_EDGE - FIR_PASSBAND_EDGE))
+      }
+    },
+    _ => {
+      let x = PI * freq_hz / sinc_notch_hz(rate, mode);
+      let sinc = if x == 0.0 { 1.0 } else { libm::sinf(x) / x };
+      libm::powf(libm::fabsf(sinc), f32::from(SINC_ORDER))
+    },
+  }
+}
+
+/// Get the magnitude response at the given frequency in decibels, relative to the response at DC.
+pub fn magnitude_db(rate: ConversionRate, filter: Filter, mode: ConversionMode, freq_hz: f32) -> f32 {
+  to_db(magnitude(rate, filter, mode, freq_hz))
+}
+
+pub(crate) fn to_db(magnitude: f32) -> f32 {
+  20.0 * libm::log10f(magnitude.max(f32::MIN_POSITIVE))
+}
+
+pub(crate) fn from_db(db: f32) -> f32 {
+  libm::powf(10.0, db / 20.0)
+}
+
+/// Get the frequency in hertz at which the magnitude response has fallen by 3 dB.
+pub fn bandwidth_3db(rate: ConversionRate, filter: Filter, mode: ConversionMode) -> f32 {
+  const ITERATIONS: u8 = 32;
+
+  let target = from_db(-3.0);
+
+  let (mut low, mut high) = match (filter, mode) {
+    (Filter::Fir | Filter::FirHighpass, ConversionMode::Continuous) => {
+      (0.0, rate.hz(mode).to_f32() * FIR_STOPBAND_EDGE)
+    },
+    _ => (0.0, sinc_notch_hz(rate, mode)),
+  };
+
+  for _ in 0..ITERATIONS {
+    let mid = (low + high) / 2.0;
+
+    if magnitude(rate, filter, mode, mid) > target {
+      low = mid;
+    } else {
+      high = mid;
+    }
+  }
+
+  (low + high) / 2.0
+}
+
+/// Get the frequencies of the notches of the magnitude response in hertz, in ascending order.
+///
+/// The FIR filter has no notches in this model.
+pub fn notches(rate: ConversionRate, filter: Filter, mode: ConversionMode) -> impl Iterator<Item = f32> {
+  let spacing = match (filter, mode) {
+    (Filter::Fir | Filter::FirHighpass, ConversionMode::Continuous) => None,
+    _ => Some(sinc_notch_hz(rate, mode)),
+  };
+
+  (1u16..).map_while(move |n| spacing.map(|spacing| spacing * f32::from(n)))
+}
+
+/// Get the group delay in seconds.
+///
+/// The SINC filter and the linear phase FIR filter have a constant group delay of half their impulse response
+/// length. For the minimum phase FIR filter, the group delay at low frequencies is modeled as a single conversion
+/// period.
+pub fn group_delay(rate: ConversionRate, filter: Filter, phase: FirPhase, mode: ConversionMode) -> f32 {
+  match (filter, mode, phase) {
+    (Filter::Fir | Filter::FirHighpass, ConversionMode::Continuous, FirPhase::Linear) => {
+      f32::from(FIR_LENGTH) / 2.0 / rate.hz(mode).to_f32()
+    },
+    (Filter::Fir | Filter::FirHighpass, ConversionMode::Continuous, FirPhase::Minimum) => 1.0 / rate.hz(mode).to_f32(),
+    _ => f32::from(SINC_ORDER) / 2.0 / sinc_notch_hz(rate, mode),
+  }
+}
+
+/// Settling behavior after starting a conversion or changing the configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settling {
+  /// Number of conversion results which are not fully settled and should be discarded.
+  pub discard: u8,
+  /// Time in nanoseconds from starting the conversion until the first fully settled result.
+  pub latency_ns: u64,
+}
+
+/// Get the settling behavior after starting a conversion.
+///
+/// In continuous mode, the filter needs its full impulse response length to settle, so all results before that are
+/// discarded. Single-cycle conversions are always settled. In continuous synchronization mode, the conversion is
+/// aligned to the next `SYNC` edge, which is modeled as one additional conversion period.
+///
+/// ```rust
+/// use max112x::{filter_response, ConversionMode, ConversionRate, Filter, SyncMode};
+///
+/// let settling = filter_response::settling(ConversionRate::Hz1000, Filter::Sinc, ConversionMode::Continuous, SyncMode::Pulse);
+/// assert_eq!(settling.discard, 3);
+/// assert_eq!(settling.latency_ns, 4_000_000);
+/// ```
+pub const fn settling(rate: ConversionRate, filter: Filter, mode: ConversionMode, sync: SyncMode) -> Settling {
+  let period_ns = rate.period_ns(mode);
+
+  let length = match (filter, mode) {
+    (_, ConversionMode::SingleCycle) => 1,
+    (Filter::Fir | Filter::FirHighpass, ConversionMode::Continuous) => FIR_LENGTH,
+    (Filter::Sinc | Filter::SincHighpass, ConversionMode::Continuous) => SINC_ORDER,
+  };
+
+  let length = match sync {
+    SyncMode::Continuous => length + 1,
+    SyncMode::Pulse => length,
+  };
+
+  Settling { discard: length - 1, latency_ns: length as u64 * period_ns }
+}
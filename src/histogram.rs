@@ -0,0 +1,202 @@
+//! Code histogram and linearity analysis of conversion results.
+
+use alloc::collections::BTreeMap;
+use core::ops::RangeInclusive;
+
+use crate::{Range, Sample};
+
+/// Input signal applied while collecting a [`Histogram`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stimulus {
+  /// A linear ramp, hitting all codes equally often.
+  Ramp,
+  /// A sine wave, hitting codes near its peaks more often.
+  Sine,
+}
+
+impl Stimulus {
+  /// Get the normalized input level below which the given fraction of results lies.
+  fn level(self, fraction: f64) -> f64 {
+    match self {
+      Self::Ramp => fraction,
+      Self::Sine => -libm::cos(core::f64::consts::PI * fraction),
+    }
+  }
+}
+
+/// Code histogram for measuring the static linearity.
+///
+/// Codes are ordered by input level, i.e. they are the decoded [values](Sample::value) of the raw `DATA` codes.
+/// Only codes which were hit are stored, so the memory use depends on the number of distinct codes rather than the
+/// size of the window.
+///
+/// The first and last code of the window also collect all results beyond the input range of the stimulus, so they
+/// are excluded from the linearity figures.
+///
+/// ```rust
+/// use max112x::histogram::{Histogram, Stimulus};
+///
+/// let mut histogram = Histogram::new(0..=7);
+/// for (code, hits) in [(0, 50), (1, 10), (2, 10), (3, 15), (4, 5), (5, 10), (6, 10), (7, 50)] {
+///   (0..hits).for_each(|_| histogram.push_value(code));
+/// }
+///
+/// let linearity = histogram.linearity(Stimulus::Ramp).unwrap();
+/// assert_eq!(linearity.max_dnl, 0.5);
+/// assert_eq!(linearity.min_dnl, -0.5);
+/// assert_eq!(linearity.max_inl, 0.5);
+/// assert_eq!(linearity.missing_codes, 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+  window: RangeInclusive<i32>,
+  counts: BTreeMap<i32, u32>,
+  below: u64,
+  above: u64,
+}
+
+impl Histogram {
+  /// Create an empty histogram over a window of codes.
+  pub const fn new(window: RangeInclusive<i32>) -> Self {
+    Self { window, counts: BTreeMap::new(), below: 0, above: 0 }
+  }
+
+  /// Create an empty histogram over all codes of the given input range.
+  pub const fn full(range: Range) -> Self {
+    Self::new(match range {
+      Range::Bipolar => -0x800000..=0x7FFFFF,
+      Range::Unipolar => 0..=0xFFFFFF,
+    })
+  }
+
+  /// Get the window of codes.
+  pub fn window(&self) -> &RangeInclusive<i32> {
+    &self.window
+  }
+
+  /// Add a conversion result.
+  pub fn push_value(&mut self, value: i32) {
+    if value < *self.window.start() {
+      self.below += 1;
+    } else if value > *self.window.end() {
+      self.above += 1;
+    } else {
+      *self.counts.entry(value).or_insert(0) += 1;
+    }
+  }
+
+  /// Add a sample if it is fresh and did not collide.
+  ///
+  /// Clipped samples are added, since they fall into the end codes.
+  pub fn push(&mut self, sample: &Sample) {
+    if sample.is_fresh() && !sample.is_collided() {
+      self.push_value(sample.value());
+    }
+  }
+
+  /// Get the number of hits of a code.
+  pub fn hits(&self, code: i32) -> u32 {
+    self.counts.get(&code).copied().unwrap_or(0)
+  }
+
+  /// Get the total number of results, including those outside of the window.
+  pub fn total(&self) -> u64 {
+    self.below + self.above + self.counts.values().map(|&n| u64::from(n)).sum::<u64>()
+  }
+
+  /// Iterate over the codes inside the window which were never hit, excluding the end codes.
+  pub fn missing_codes(&self) -> impl Iterator<Item = i32> + '_ {
+    self.inner_codes().filter(|code| !self.counts.contains_key(code))
+  }
+
+  /// Iterate over the differential and integral nonlinearity of each code, excluding the end codes.
+  ///
+  /// Returns `None` if the window contains less than three codes or no results were added.
+  pub fn codes(&self, stimulus: Stimulus) -> Option<impl Iterator<Item = CodeLinearity> + '_> {
+    let total = self.total() as f64;
+    let inner = i64::from(*self.window.end()) - i64::from(*self.window.start()) - 1;
+    if total == 0.0 || inner <= 0 {
+      return None
+    }
+
+    // Input level of the lower transition of the first inner code and the upper transition of the last one.
+    let start = self.below + u64::from(self.hits(*self.window.start()));
+    let end = self.total() - self.above - u64::from(self.hits(*self.window.end()));
+    let level = move |cumulative: u64| stimulus.level(cumulative as f64 / total);
+    let width = (level(end) - level(start)) / inner as f64;
+
+    let mut cumulative = start;
+    let mut inl = 0.0;
+    Some(self.inner_codes().map(move |code| {
+      let hits = u64::from(self.hits(code));
+      let dnl = (level(cumulative + hits) - level(cumulative)) / width - 1.0;
+      cumulative += hits;
+      inl += dnl;
+
+      CodeLinearity { code, hits: hits as u32, dnl: dnl as f32, inl: inl as f32 }
+    }))
+  }
+
+  /// Summarize the linearity over all codes, excluding the end codes.
+  ///
+  /// Returns `None` if the window contains less than three codes or no results were added.
+  pub fn linearity(&self, stimulus: Stimulus) -> Option<Linearity> {
+    let init = Linearity {
+      missing_codes: 0,
+      min_dnl: f32::INFINITY,
+      max_dnl: f32::NEG_INFINITY,
+      min_inl: f32::INFINITY,
+      max_inl: f32::NEG_INFINITY,
+    };
+
+    Some(self.codes(stimulus)?.fold(init, |mut linearity, code| {
+      if code.hits == 0 {
+        linearity.missing_codes += 1;
+      }
+      linearity.min_dnl = linearity.min_dnl.min(code.dnl);
+      linearity.max_dnl = linearity.max_dnl.max(code.dnl);
+      linearity.min_inl = linearity.min_inl.min(code.inl);
+      linearity.max_inl = linearity.max_inl.max(code.inl);
+      linearity
+    }))
+  }
+
+  /// Remove all results.
+  pub fn clear(&mut self) {
+    self.counts.clear();
+    self.below = 0;
+    self.above = 0;
+  }
+
+  fn inner_codes(&self) -> RangeInclusive<i32> {
+    self.window.start().saturating_add(1)..=self.window.end().saturating_sub(1)
+  }
+}
+
+/// Linearity of a single code.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CodeLinearity {
+  /// The code.
+  pub code: i32,
+  /// Number of hits.
+  pub hits: u32,
+  /// Differential nonlinearity in LSB.
+  pub dnl: f32,
+  /// Integral nonlinearity in LSB at the upper transition of the code, relative to the line through the end points.
+  pub inl: f32,
+}
+
+/// Summary of the linearity of all codes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Linearity {
+  /// Number of codes which were never hit.
+  pub missing_codes: u32,
+  /// Minimum differential nonlinearity in LSB, which is -1 for missing codes.
+  pub min_dnl: f32,
+  /// Maximum differential nonlinearity in LSB.
+  pub max_dnl: f32,
+  /// Minimum integral nonlinearity in LSB.
+  pub min_inl: f32,
+  /// Maximum integral nonlinearity in LSB.
+  pub max_inl: f32,
+}
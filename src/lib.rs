@@ -55,16 +55,52 @@
 #![warn(missing_debug_implementations)]
 #![warn(missing_docs)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 use core::marker::PhantomData;
 
 use embedded_hal::spi::{Operation, SpiDevice};
 
+pub mod allan;
+mod autorange;
+pub use autorange::{AutoRange, GainCalibration, RangedSample, SelfCalibrate};
+mod cache;
+use cache::Cache;
+mod calibration;
+pub use calibration::{CalibrationData, CalibrationEntry, CalibrationTable};
+pub mod capture;
 mod command;
 use command::Command;
+mod config;
+pub use config::Config;
+mod correction;
+pub use correction::{CalibrationRecord, Correction};
+pub mod dsp;
 mod error;
 pub use error::Error;
+pub mod filter_response;
+#[cfg(feature = "alloc")]
+pub mod histogram;
+pub mod mains;
+pub mod noise;
+mod ready;
+pub use ready::{DataReady, PollStatus, ReadyPin, Samples};
+mod recalibration;
+pub use recalibration::{Recalibrating, RecalibrationSchedule, Thermometer};
 mod register;
 use register::*;
+mod sample;
+pub use sample::Sample;
+mod snapshot;
+pub use snapshot::RegisterSnapshot;
+pub mod spectrum;
+mod system_calibration;
+pub use system_calibration::{FullScaleInput, SystemCalibrator, Unvalidated, Validated, ZeroInput};
+#[cfg(test)]
+mod tests;
+mod timestamp;
+pub use timestamp::{Clock, Nominal, Timebase, Timestamped, TimestampedSample};
 mod types;
 pub use types::*;
 
@@ -80,43 +116,78 @@ pub enum Sleep {}
 #[derive(Debug)]
 pub enum Standby {}
 
+/// Settings of the last started conversion.
+#[derive(Debug, Clone, Copy)]
+struct ConversionSettings {
+  rate: ConversionRate,
+  continuous: bool,
+  range: Range,
+  format: Format,
+  data32: bool,
+  unsettled: u8,
+}
+
 /// A MAX11214 ADC.
+///
+/// Optionally holds a [`CalibrationTable`] with up to `N` entries, see [`Max11214::with_calibration_table`].
 #[derive(Debug)]
-pub struct Max11214<SPI, MODE> {
+pub struct Max11214<SPI, MODE, const N: usize = 0> {
   spi: SPI,
+  cache: Cache,
+  verify_retries: Option<u8>,
+  skip_unsettled: bool,
+  calibration_table: CalibrationTable<N>,
+  loaded_calibration: Option<(Option<Pga>, ConversionRate)>,
+  conversion: ConversionSettings,
   mode: PhantomData<MODE>,
 }
 
 impl<SPI> Max11214<SPI, Standby> {
   /// Create a new ADC with the given SPI peripheral.
   pub const fn new(spi: SPI) -> Self {
-    Self { spi, mode: PhantomData }
+    Self {
+      spi,
+      cache: Cache::new(),
+      verify_retries: None,
+      skip_unsettled: false,
+      calibration_table: CalibrationTable::new(),
+      loaded_calibration: None,
+      conversion: ConversionSettings {
+        rate: ConversionRate::Hz0_95,
+        continuous: true,
+        range: Range::Bipolar,
+        format: Format::TwosComplement,
+        data32: false,
+        unsettled: 0,
+      },
+      mode: PhantomData,
+    }
   }
+}
 
+impl<SPI, const N: usize> Max11214<SPI, Standby, N> {
   /// Release the contained SPI peripheral.
   pub fn release(self) -> SPI {
     self.spi
   }
 }
 
-impl<SPI, E, MODE> Max11214<SPI, MODE>
+impl<SPI, E, MODE, const N: usize> Max11214<SPI, MODE, N>
 where
   SPI: SpiDevice<u8, Error = E>,
 {
   /// Put the ADC into standby mode.
-  pub fn into_standby(mut self) -> Result<Max11214<SPI, Standby>, Error<E>> {
-    self.modify_reg_u8(|ctrl1: Ctrl1| ctrl1.union(Ctrl1::PD1).difference(Ctrl1::PD0))?;
-
-    self.write_cmd(Command::power_down())?;
-    Ok(Max11214 { spi: self.spi, mode: PhantomData })
+  pub fn into_standby(mut self) -> Result<Max11214<SPI, Standby, N>, Error<E>> {
+    self.standby()?;
+    Ok(self.into_mode())
   }
 
   /// Put the ADC into sleep mode.
-  pub fn into_sleep(mut self) -> Result<Max11214<SPI, Sleep>, Error<E>> {
+  pub fn into_sleep(mut self) -> Result<Max11214<SPI, Sleep, N>, Error<E>> {
     self.modify_reg_u8(|ctrl1: Ctrl1| ctrl1.difference(Ctrl1::PD1).union(Ctrl1::PD0))?;
 
     self.write_cmd(Command::power_down())?;
-    Ok(Max11214 { spi: self.spi, mode: PhantomData })
+    Ok(self.into_mode())
   }
 
   /// Start conversion.
@@ -124,22 +195,418 @@ where
     mut self,
     rate: ConversionRate,
     continuous: bool,
-  ) -> Result<Max11214<SPI, Conversion>, Error<E>> {
-    self.modify_reg_u8(|mut ctrl1: Ctrl1| {
-      ctrl1.set(Ctrl1::SCYCLE, !continuous);
-      ctrl1.difference(Ctrl1::PD1).difference(Ctrl1::PD0)
-    })?;
-
-    self.write_cmd(Command::convert(rate))?;
-    Ok(Max11214 { spi: self.spi, mode: PhantomData })
+  ) -> Result<Max11214<SPI, Conversion, N>, Error<E>> {
+    self.convert(rate, continuous)?;
+    Ok(self.into_mode())
   }
 
   /// Get the system status.
+  ///
+  /// If the ADC is in reset, the register cache is invalidated.
   pub fn status(&mut self) -> Result<Status, Error<E>> {
     let stat = self.read_reg_u16::<Stat>()?;
+
+    if stat.contains(Stat::INRESET) {
+      self.cache.invalidate();
+      self.loaded_calibration = None;
+    }
+
     Ok(Status { status: stat })
   }
 
+  /// Enable the register cache.
+  ///
+  /// When enabled, a copy of the `CTRL1` to `CTRL5` registers is kept, so changing settings only needs a single
+  /// register write and no register write at all if the value is unchanged. The cache is filled by reading the
+  /// registers once.
+  ///
+  /// ```rust
+  /// # fn main() -> Result<(), max112x::Error<embedded_hal::spi::ErrorKind>> {
+  /// # use embedded_hal_mock::eh1::{spi::{Mock as SpiMock, Transaction as SpiTransaction}};
+  /// # let mut transactions = vec![];
+  /// # for (cmd, value) in [(0b11000011, 0b00100000), (0b11000101, 0), (0b11000111, 0), (0b11001001, 0), (0b11001011, 0)] {
+  /// #   transactions.push(SpiTransaction::transaction_start());
+  /// #   transactions.push(SpiTransaction::transfer_in_place(vec![cmd, 0], vec![cmd, value]));
+  /// #   transactions.push(SpiTransaction::transaction_end());
+  /// # }
+  /// # transactions.push(SpiTransaction::transaction_start());
+  /// # transactions.push(SpiTransaction::write_vec(vec![0b11000100, 0b00001010]));
+  /// # transactions.push(SpiTransaction::transaction_end());
+  /// # let spi = SpiMock::new(&transactions);
+  /// use max112x::{Max11214, Pga};
+  ///
+  /// let mut adc = Max11214::new(spi);
+  /// adc.enable_cache()?;
+  ///
+  /// // Only writes `CTRL2`.
+  /// adc.set_pga(Some(Pga::X4))?;
+  ///
+  /// // Does not access the ADC at all.
+  /// adc.set_pga(Some(Pga::X4))?;
+  /// # let mut spi = adc.release();
+  /// # spi.done();
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn enable_cache(&mut self) -> Result<(), Error<E>> {
+    self.cache.set_enabled(true);
+    self.resync()
+  }
+
+  /// Disable the register cache.
+  pub fn disable_cache(&mut self) {
+    self.cache.set_enabled(false);
+  }
+
+  /// Set whether conversion results which are not fully settled are skipped.
+  ///
+  /// When enabled, the number of unsettled results is determined using [`filter_response::settling`] when starting a
+  /// conversion, and these results are skipped by [`Max11214::samples`], [`Max11214::acquire`] and related methods.
+  pub fn set_skip_unsettled(&mut self, skip: bool) {
+    self.skip_unsettled = skip;
+  }
+
+  /// Enable write verification.
+  ///
+  /// When enabled, every control register write is read back and compared. On mismatch, the write is retried up to
+  /// `retries` times before returning [`Error::VerifyFailed`]. After a failed verification, the register cache is
+  /// invalidated.
+  ///
+  /// ```rust
+  /// # use embedded_hal::spi::SpiDevice;
+  /// # use max112x::{Error, Max11214, Standby};
+  /// # fn example<SPI: SpiDevice>(adc: &mut Max11214<SPI, Standby>) -> Result<(), Error<SPI::Error>> {
+  /// use max112x::Pga;
+  ///
+  /// adc.enable_write_verification(2);
+  ///
+  /// if let Err(Error::VerifyFailed { addr, wrote, read }) = adc.set_pga(Some(Pga::X2)) {
+  ///   // The register at `addr` still reads `read` instead of `wrote`.
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn enable_write_verification(&mut self, retries: u8) {
+    self.verify_retries = Some(retries);
+  }
+
+  /// Disable write verification.
+  pub fn disable_write_verification(&mut self) {
+    self.verify_retries = None;
+  }
+
+  /// Use a calibration table to load calibration coefficients from automatically.
+  ///
+  /// Whenever a conversion is started, including after [`Max11214::reconfigure`] and [`Max11214::recalibrate`], the
+  /// coefficients for the current PGA gain and conversion rate are written to the calibration registers if they
+  /// differ from the last loaded combination. Combinations without an entry leave the coefficients unchanged.
+  ///
+  /// The table is usually filled using `calibrate_table`.
+  ///
+  /// ```rust
+  /// # use embedded_hal::spi::SpiDevice;
+  /// # use max112x::{Error, Max11214, Standby};
+  /// # fn example<SPI: SpiDevice>(adc: Max11214<SPI, Standby>) -> Result<(), Error<SPI::Error>> {
+  /// use max112x::{CalibrationTable, ConversionRate, Pga};
+  ///
+  /// let mut adc = adc.with_calibration_table(CalibrationTable::<4>::new());
+  /// adc.calibrate_table(&[(Some(Pga::X2), ConversionRate::Hz1000)])?;
+  ///
+  /// // Loads the coefficients for PGA X2 at 1000 Hz before starting the conversion.
+  /// let adc = adc.start_conversion(ConversionRate::Hz1000, true)?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn with_calibration_table<const M: usize>(self, table: CalibrationTable<M>) -> Max11214<SPI, MODE, M> {
+    Max11214 {
+      spi: self.spi,
+      cache: self.cache,
+      verify_retries: self.verify_retries,
+      skip_unsettled: self.skip_unsettled,
+      calibration_table: table,
+      loaded_calibration: None,
+      conversion: self.conversion,
+      mode: PhantomData,
+    }
+  }
+
+  /// Get the calibration table.
+  pub fn calibration_table(&self) -> &CalibrationTable<N> {
+    &self.calibration_table
+  }
+
+  /// Get the calibration table mutably.
+  ///
+  /// The coefficients are loaded again when the next conversion is started.
+  pub fn calibration_table_mut(&mut self) -> &mut CalibrationTable<N> {
+    self.loaded_calibration = None;
+    &mut self.calibration_table
+  }
+
+  /// Re-read all cached registers from the ADC.
+  ///
+  /// This should be called if the ADC may have been reset externally, e.g. using the `RSTB` pin.
+  pub fn resync(&mut self) -> Result<(), Error<E>> {
+    if self.cache.is_enabled() {
+      self.cache.invalidate();
+      self.cached_reg_u8::<Ctrl1>()?;
+      self.cached_reg_u8::<Ctrl2>()?;
+      self.cached_reg_u8::<Ctrl3>()?;
+      self.cached_reg_u8::<Ctrl4>()?;
+      self.cached_reg_u8::<Ctrl5>()?;
+    }
+
+    Ok(())
+  }
+
+  /// Get the system clock source.
+  pub fn clock(&mut self) -> Result<ClockSource, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl1>()?.clock())
+  }
+
+  /// Get the synchronization mode.
+  pub fn sync(&mut self) -> Result<SyncMode, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl1>()?.sync())
+  }
+
+  /// Get the input range.
+  pub fn range(&mut self) -> Result<Range, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl1>()?.range())
+  }
+
+  /// Get the bipolar range format.
+  pub fn format(&mut self) -> Result<Format, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl1>()?.format())
+  }
+
+  /// Check if single-cycle conversions are repeated continuously.
+  pub fn continuous_single_cycle(&mut self) -> Result<bool, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl1>()?.contains(Ctrl1::CONTSC))
+  }
+
+  /// Get the modulator digital gain.
+  pub fn digital_gain(&mut self) -> Result<DigitalGain, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl2>()?.digital_gain())
+  }
+
+  /// Check if the analog input buffers are enabled.
+  pub fn input_buffer(&mut self) -> Result<bool, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl2>()?.contains(Ctrl2::BUFEN))
+  }
+
+  /// Check if the PGA is in low power mode.
+  pub fn pga_low_power(&mut self) -> Result<bool, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl2>()?.contains(Ctrl2::LPMODE))
+  }
+
+  /// Get the PGA gain, or `None` if the PGA is disabled.
+  pub fn pga(&mut self) -> Result<Option<Pga>, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl2>()?.pga())
+  }
+
+  /// Check if the modulator sync pulse is enabled.
+  pub fn modulator_sync(&mut self) -> Result<bool, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl3>()?.contains(Ctrl3::ENMSYNC))
+  }
+
+  /// Check if the modulator output mode is enabled.
+  pub fn modulator_output(&mut self) -> Result<bool, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl3>()?.contains(Ctrl3::MODBITS))
+  }
+
+  /// Check if 32-bit data output is enabled.
+  pub fn data32(&mut self) -> Result<bool, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl3>()?.contains(Ctrl3::DATA32))
+  }
+
+  /// Get the digital filter.
+  pub fn filter(&mut self) -> Result<Filter, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl3>()?.filter())
+  }
+
+  /// Get the FIR filter phase response.
+  pub fn fir_phase(&mut self) -> Result<FirPhase, Error<E>> {
+    Ok(self.cached_reg_u8::<Ctrl3>()?.fir_phase())
+  }
+
+  /// Check if the self-calibration offset coefficient is applied.
+  pub fn self_offset_calibration(&mut self) -> Result<bool, Error<E>> {
+    Ok(!self.cached_reg_u8::<Ctrl5>()?.contains(Ctrl5::NOSCO))
+  }
+
+  /// Check if the self-calibration gain coefficient is applied.
+  pub fn self_gain_calibration(&mut self) -> Result<bool, Error<E>> {
+    Ok(!self.cached_reg_u8::<Ctrl5>()?.contains(Ctrl5::NOSCG))
+  }
+
+  /// Check if the system offset calibration coefficient is applied.
+  pub fn system_offset_calibration(&mut self) -> Result<bool, Error<E>> {
+    Ok(!self.cached_reg_u8::<Ctrl5>()?.contains(Ctrl5::NOSYSO))
+  }
+
+  /// Check if the system gain calibration coefficient is applied.
+  pub fn system_gain_calibration(&mut self) -> Result<bool, Error<E>> {
+    Ok(!self.cached_reg_u8::<Ctrl5>()?.contains(Ctrl5::NOSYSG))
+  }
+
+  /// Read the current configuration.
+  pub fn read_config(&mut self) -> Result<Config, Error<E>> {
+    Ok(Config::from_regs(
+      self.cached_reg_u8()?,
+      self.cached_reg_u8()?,
+      self.cached_reg_u8()?,
+      self.cached_reg_u8()?,
+      self.cached_reg_u8()?,
+    ))
+  }
+
+  /// Read all registers.
+  ///
+  /// Note that this reads the DATA register, which clears the data ready status.
+  ///
+  /// ```rust
+  /// # use embedded_hal::spi::SpiDevice;
+  /// # use max112x::{Error, Max11214, Standby};
+  /// # fn example<SPI: SpiDevice>(adc: &mut Max11214<SPI, Standby>) -> Result<(), Error<SPI::Error>> {
+  /// let snapshot = adc.dump_registers()?;
+  /// println!("{snapshot:?}");
+  ///
+  /// // Write the configuration back, e.g. after a reset.
+  /// adc.restore_registers(&snapshot)?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn dump_registers(&mut self) -> Result<RegisterSnapshot, Error<E>> {
+    let stat = self.read_reg_u16::<Stat>()?;
+    let ctrl1 = self.read_reg_u8::<Ctrl1>()?;
+    let ctrl2 = self.read_reg_u8::<Ctrl2>()?;
+    let ctrl3 = self.read_reg_u8::<Ctrl3>()?;
+    let ctrl4 = self.read_reg_u8::<Ctrl4>()?;
+    let ctrl5 = self.read_reg_u8::<Ctrl5>()?;
+    let data = if ctrl3.contains(Ctrl3::DATA32) {
+      self.read_reg_u32::<Data32>()?.0
+    } else {
+      self.read_reg_u24::<Data24>()?.0.into()
+    };
+
+    Ok(RegisterSnapshot {
+      stat,
+      ctrl1,
+      ctrl2,
+      ctrl3,
+      ctrl4,
+      ctrl5,
+      data,
+      soc_spi: self.read_reg_u24()?,
+      sgc_spi: self.read_reg_u24()?,
+      scoc_spi: self.read_reg_u24()?,
+      scgc_spi: self.read_reg_u24()?,
+      hpf: self.read_reg_u16()?,
+      soc_adc: self.read_reg_u24()?,
+      sgc_adc: self.read_reg_u24()?,
+      scoc_adc: self.read_reg_u24()?,
+      scgc_adc: self.read_reg_u24()?,
+    })
+  }
+
+  fn standby(&mut self) -> Result<(), Error<E>> {
+    self.modify_reg_u8(|ctrl1: Ctrl1| ctrl1.union(Ctrl1::PD1).difference(Ctrl1::PD0))?;
+
+    self.write_cmd(Command::power_down())
+  }
+
+  /// Start converting.
+  ///
+  /// Returns the settling behavior, without any results to discard if only a single conversion is performed.
+  fn convert(&mut self, rate: ConversionRate, continuous: bool) -> Result<filter_response::Settling, Error<E>> {
+    let mut ctrl1 = Ctrl1::empty();
+
+    self.modify_reg_u8(|mut reg: Ctrl1| {
+      reg.set(Ctrl1::SCYCLE, !continuous);
+      ctrl1 = reg.difference(Ctrl1::PD1).difference(Ctrl1::PD0);
+      ctrl1
+    })?;
+
+    let ctrl3 = self.cached_reg_u8::<Ctrl3>()?;
+
+    if N > 0 {
+      let pga = self.cached_reg_u8::<Ctrl2>()?.pga();
+
+      if self.loaded_calibration != Some((pga, rate)) {
+        if let Some(data) = self.calibration_table.get(pga, rate).copied() {
+          self.write_calibration(&data)?;
+        }
+        self.loaded_calibration = Some((pga, rate));
+      }
+    }
+
+    self.write_cmd(Command::convert(rate))?;
+
+    let mode = ConversionMode::from_continuous(continuous);
+    let mut settling = filter_response::settling(rate, ctrl3.filter(), mode, ctrl1.sync());
+    if !continuous && !ctrl1.contains(Ctrl1::CONTSC) {
+      settling.discard = 0;
+    }
+
+    let unsettled = if self.skip_unsettled { settling.discard } else { 0 };
+
+    self.conversion = ConversionSettings {
+      rate,
+      continuous,
+      range: ctrl1.range(),
+      format: ctrl1.format(),
+      data32: ctrl3.contains(Ctrl3::DATA32),
+      unsettled,
+    };
+    Ok(settling)
+  }
+
+  fn calibrate(&mut self, calibration: Calibration, rate: ConversionRate) -> Result<(), Error<E>> {
+    self.modify_reg_u8(|ctrl1: Ctrl5| match calibration {
+      Calibration::SelfCalibration => ctrl1.difference(Ctrl5::CAL),
+      Calibration::SystemOffsetCalibration => ctrl1.difference(Ctrl5::CAL1).union(Ctrl5::CAL0),
+      Calibration::SystemFullScaleCalibration => ctrl1.union(Ctrl5::CAL1).difference(Ctrl5::CAL0),
+    })?;
+    self.loaded_calibration = None;
+
+    let duration = calibration.duration_ns();
+    self
+      .spi
+      .transaction(&mut [Operation::Write(&[Command::calibrate(rate).bits()]), Operation::DelayNs(duration)])
+      .map_err(|err| Error::Spi(err))?;
+
+    Ok(())
+  }
+
+  fn write_calibration(&mut self, data: &CalibrationData) -> Result<(), Error<E>> {
+    self.loaded_calibration = None;
+    self.write_reg_u24(SocSpi(u24::truncate(data.system_offset)))?;
+    self.write_reg_u24(SgcSpi(u24::truncate(data.system_gain)))?;
+    self.write_reg_u24(ScocSpi(u24::truncate(data.self_offset)))?;
+    self.write_reg_u24(ScgcSpi(u24::truncate(data.self_gain)))
+  }
+
+  fn write_config(&mut self, config: &Config) -> Result<(), Error<E>> {
+    self.modify_reg_u8(|ctrl1| config.ctrl1(ctrl1))?;
+    self.modify_reg_u8(|ctrl2| config.ctrl2(ctrl2))?;
+    self.modify_reg_u8(|ctrl3| config.ctrl3(ctrl3))?;
+    self.modify_reg_u8(|ctrl4| config.ctrl4(ctrl4))?;
+    self.modify_reg_u8(|ctrl5| config.ctrl5(ctrl5))
+  }
+
+  fn into_mode<M>(self) -> Max11214<SPI, M, N> {
+    Max11214 {
+      spi: self.spi,
+      cache: self.cache,
+      verify_retries: self.verify_retries,
+      skip_unsettled: self.skip_unsettled,
+      calibration_table: self.calibration_table,
+      loaded_calibration: self.loaded_calibration,
+      conversion: self.conversion,
+      mode: PhantomData,
+    }
+  }
+
   fn write_cmd(&mut self, cmd: Command) -> Result<(), Error<E>> {
     let cmd = [cmd.bits()];
     self.spi.write(&cmd).map_err(|err| Error::Spi(err))?;
@@ -150,7 +617,7 @@ where
   where
     R: WriteReg<u8> + PartialEq + Copy,
   {
-    let reg = self.read_reg_u8::<R>()?;
+    let reg = self.cached_reg_u8::<R>()?;
     let new_reg = f(reg);
 
     if new_reg != reg {
@@ -164,10 +631,66 @@ where
   where
     R: WriteReg<u8>,
   {
-    let buf = [Command::register_write(R::ADDR).bits(), reg.to_reg()];
+    let value = reg.to_reg();
+    let buf = [Command::register_write(R::ADDR).bits(), value];
+
+    let Some(retries) = self.verify_retries else {
+      self.spi.write(&buf).map_err(|err| Error::Spi(err))?;
+      self.cache.set(R::ADDR, value);
+      return Ok(())
+    };
+
+    let mask = readback_mask(R::ADDR, value);
+    let mut read = 0;
+
+    for _ in 0..=retries {
+      self.spi.write(&buf).map_err(|err| Error::Spi(err))?;
+
+      let mut read_buf = [Command::register_read(R::ADDR).bits(), 0];
+      self.spi.transfer_in_place(read_buf.as_mut()).map_err(|err| Error::Spi(err))?;
+      read = read_buf[1];
+
+      if read & mask == value & mask {
+        self.cache.set(R::ADDR, value);
+        return Ok(())
+      }
+    }
+
+    self.cache.invalidate();
+    Err(Error::VerifyFailed { addr: R::ADDR, wrote: value, read })
+  }
+
+  fn write_reg_u16<R>(&mut self, reg: R) -> Result<(), Error<E>>
+  where
+    R: WriteReg<u16>,
+  {
+    let [b1, b0] = reg.to_reg().to_be_bytes();
+    let buf = [Command::register_write(R::ADDR).bits(), b1, b0];
     self.spi.write(&buf).map_err(|err| Error::Spi(err))
   }
 
+  fn write_reg_u24<R>(&mut self, reg: R) -> Result<(), Error<E>>
+  where
+    R: WriteReg<u24>,
+  {
+    let [b2, b1, b0] = reg.to_reg().to_be_bytes();
+    let buf = [Command::register_write(R::ADDR).bits(), b2, b1, b0];
+    self.spi.write(&buf).map_err(|err| Error::Spi(err))
+  }
+
+  fn cached_reg_u8<R>(&mut self) -> Result<R, Error<E>>
+  where
+    R: WriteReg<u8> + Copy,
+  {
+    if let Some(value) = self.cache.get(R::ADDR) {
+      return Ok(R::from_reg(value))
+    }
+
+    let reg = self.read_reg_u8::<R>()?;
+    self.cache.set(R::ADDR, reg.to_reg());
+    Ok(reg)
+  }
+
   fn read_reg_u8<R>(&mut self) -> Result<R, Error<E>>
   where
     R: ReadReg<u8>,
@@ -201,7 +724,6 @@ where
     Ok(R::from_reg(u24::from_be_bytes([buf[1], buf[2], buf[3]])))
   }
 
-  #[allow(unused)]
   fn read_reg_u32<R>(&mut self) -> Result<R, Error<E>>
   where
     R: ReadReg<u32>,
@@ -214,14 +736,455 @@ where
   }
 }
 
-impl<SPI, E> Max11214<SPI, Conversion>
+impl<SPI, E, const N: usize> Max11214<SPI, Conversion, N>
 where
   SPI: SpiDevice<u8, Error = E>,
 {
   /// Read data.
+  ///
+  /// Returns the 32-bit DATA register if 32-bit data output was enabled when the conversion was started, otherwise
+  /// the 24-bit DATA register.
   pub fn data(&mut self) -> Result<u32, Error<E>> {
-    let data = self.read_reg_u24::<Data24>()?;
-    Ok(data.0.into())
+    if self.conversion.data32 {
+      Ok(self.read_reg_u32::<Data32>()?.0)
+    } else {
+      Ok(self.read_reg_u24::<Data24>()?.0.into())
+    }
+  }
+
+  /// Read data together with the status.
+  ///
+  /// This reads the `STAT` register to check whether the result is new and whether it was clipped, then the `DATA`
+  /// register, and finally the `STAT` register again to check whether the DATA register was updated while being read.
+  /// These are three separate SPI transactions, since every register access has to be framed by chip select.
+  ///
+  /// With 32-bit data output, the 24-bit result is taken from the upper 24 bits of the DATA register.
+  ///
+  /// ```rust
+  /// # fn main() -> Result<(), max112x::Error<embedded_hal::spi::ErrorKind>> {
+  /// # use embedded_hal_mock::eh1::{spi::{Mock as SpiMock, Transaction as SpiTransaction}};
+  /// # let spi = SpiMock::new(&[
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::transfer_in_place(vec![0b11000011, 0], vec![0b11000011, 0b00100000]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0b11000010, 0b00000000]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::transfer_in_place(vec![0b11000111, 0], vec![0b11000111, 0b00000000]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0b10000111]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::transfer_in_place(vec![0b11000001, 0, 0], vec![0b11000001, 0b00000000, 0b01110011]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::transfer_in_place(vec![0b11001101, 0, 0, 0], vec![0b11001101, 0xFF, 0xFF, 0xFE]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::transfer_in_place(vec![0b11000001, 0, 0], vec![0b11000001, 0b00000000, 0b01110010]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::transfer_in_place(vec![0b11000011, 0], vec![0b11000011, 0b00000000]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0b11000010, 0b00100000]),
+  /// #   SpiTransaction::transaction_end(),
+  /// #   SpiTransaction::transaction_start(),
+  /// #   SpiTransaction::write_vec(vec![0b10010000]),
+  /// #   SpiTransaction::transaction_end(),
+  /// # ]);
+  /// use max112x::{ConversionRate, Max11214};
+  ///
+  /// let adc = Max11214::new(spi);
+  /// let mut adc = adc.start_conversion(ConversionRate::Hz125, true)?;
+  ///
+  /// let sample = adc.read_sample()?;
+  /// assert!(sample.is_valid());
+  /// assert_eq!(sample.value(), -2);
+  /// # let mut spi = adc.into_standby()?.release();
+  /// # spi.done();
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn read_sample(&mut self) -> Result<Sample, Error<E>> {
+    let status = self.status()?;
+    let data = self.data()?;
+    let read_error = self.status()?.data_read_error();
+
+    let ConversionSettings { range, format, data32, .. } = self.conversion;
+    let code = if data32 { data >> 8 } else { data };
+    Ok(Sample::new(code, range, format, status, read_error))
+  }
+
+  /// Fill a buffer with consecutive conversion results.
+  ///
+  /// Results which were overwritten while being read are skipped. Returns the number of skipped results.
+  ///
+  /// ```rust
+  /// # use embedded_hal::spi::SpiDevice;
+  /// # use max112x::{Conversion, DataReady, Error, Max11214};
+  /// # fn example<SPI: SpiDevice>(adc: &mut Max11214<SPI, Conversion>, ready: &mut impl DataReady) -> Result<(), Error<SPI::Error>> {
+  /// let mut buf = [0; 64];
+  /// let missed = adc.acquire(&mut buf, ready)?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn acquire<R>(&mut self, buf: &mut [i32], ready: &mut R) -> Result<usize, Error<E>>
+  where
+    R: DataReady,
+  {
+    let mut missed = 0;
+
+    for value in buf {
+      *value = self.next_value(ready, &mut missed)?;
+    }
+
+    Ok(missed)
+  }
+
+  /// Push `count` consecutive conversion results into a queue.
+  ///
+  /// This can be used in an interrupt handler triggered by the `RDYB` pin to feed a consumer in the main loop.
+  /// Results which were overwritten while being read or which did not fit into the queue are skipped. Returns the
+  /// number of skipped results.
+  ///
+  /// ```rust
+  /// # use embedded_hal::spi::SpiDevice;
+  /// # use max112x::{Conversion, DataReady, Error, Max11214};
+  /// # fn example<SPI: SpiDevice>(adc: &mut Max11214<SPI, Conversion>, ready: &mut impl DataReady) -> Result<(), Error<SPI::Error>> {
+  /// use heapless::spsc::Queue;
+  ///
+  /// let mut queue = Queue::<i32, 64>::new();
+  /// let (mut producer, mut consumer) = queue.split();
+  ///
+  /// // E.g. in the `RDYB` interrupt handler.
+  /// let missed = adc.acquire_into_queue(&mut producer, 1, ready)?;
+  ///
+  /// // E.g. in the main loop.
+  /// while let Some(value) = consumer.dequeue() {
+  ///   // Process the result.
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  #[cfg(feature = "heapless")]
+  pub fn acquire_into_queue<R, const M: usize>(
+    &mut self,
+    producer: &mut heapless::spsc::Producer<'_, i32, M>,
+    count: usize,
+    ready: &mut R,
+  ) -> Result<usize, Error<E>>
+  where
+    R: DataReady,
+  {
+    let mut missed = 0;
+
+    for _ in 0..count {
+      let value = self.next_value(ready, &mut missed)?;
+
+      if producer.enqueue(value).is_err() {
+        missed += 1;
+      }
+    }
+
+    Ok(missed)
+  }
+
+  /// Get an iterator over conversion results.
+  ///
+  /// The iterator never ends, so it is usually combined with [`Iterator::take`].
+  pub fn samples<'a, R>(&'a mut self, ready: &'a mut R) -> Samples<'a, SPI, R, N>
+  where
+    R: DataReady,
+  {
+    Samples { adc: self, ready }
+  }
+
+  /// Get an iterator over conversion results tagged with an index and a timestamp.
+  ///
+  /// Timestamps are either taken from a [`Clock`] or derived from the nominal conversion rate using [`Nominal`].
+  ///
+  /// ```rust
+  /// # use embedded_hal::spi::SpiDevice;
+  /// # use max112x::{Conversion, DataReady, Error, Max11214};
+  /// # fn example<SPI: SpiDevice>(adc: &mut Max11214<SPI, Conversion>, ready: &mut impl DataReady) -> Result<(), Error<SPI::Error>> {
+  /// use max112x::Nominal;
+  ///
+  /// for sample in adc.timestamped_samples(ready, Nominal).take(100) {
+  ///   let sample = sample?;
+  ///
+  ///   if sample.is_discontinuity() {
+  ///     // Handle the gap of `sample.missed` results before `sample.index`.
+  ///   }
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn timestamped_samples<'a, R, T>(&'a mut self, ready: &'a mut R, timebase: T) -> Timestamped<'a, SPI, R, T, N>
+  where
+    R: DataReady,
+    T: Timebase,
+  {
+    let ConversionSettings { rate, continuous, .. } = self.conversion;
+    let period_ns = rate.period_ns(ConversionMode::from_continuous(continuous));
+
+    Timestamped {
+      samples: self.samples(ready),
+      timebase,
+      period_ns,
+      next_index: 0,
+      last_timestamp_ns: None,
+      skipped: 0,
+    }
+  }
+
+  /// Get a stream of conversion results, waiting for the `RDYB` pin asynchronously.
+  ///
+  /// Only waiting for the pin is asynchronous, the results are read using the blocking SPI peripheral.
+  #[cfg(feature = "async")]
+  pub fn sample_stream<'a, P>(
+    &'a mut self,
+    rdy: &'a mut P,
+  ) -> impl futures_util::Stream<Item = Result<Sample, Error<E>>> + 'a
+  where
+    P: embedded_hal_async::digital::Wait,
+  {
+    use embedded_hal::digital::Error as _;
+
+    futures_util::stream::unfold((self, rdy), |(adc, rdy)| async move {
+      let sample = loop {
+        if let Err(err) = rdy.wait_for_low().await {
+          break Err(Error::Pin(err.kind()))
+        }
+
+        match adc.read_sample() {
+          Ok(sample) if adc.skip_sample(&sample) => continue,
+          res => break res,
+        }
+      };
+
+      Some((sample, (adc, rdy)))
+    })
+  }
+
+  fn next_value<R>(&mut self, ready: &mut R, missed: &mut usize) -> Result<i32, Error<E>>
+  where
+    R: DataReady,
+  {
+    loop {
+      let sample = self.wait_sample(ready)?;
+
+      if sample.is_collided() {
+        *missed += 1;
+      } else if sample.is_fresh() {
+        return Ok(sample.value())
+      }
+    }
+  }
+
+  /// Measure the noise of `n` conversion results.
+  ///
+  /// Collided results are skipped. Clipped and overranged results are read but excluded from the statistics, like
+  /// [`noise::Statistics::push_sample`] does. The results are converted into microvolts using the given reference
+  /// voltage in microvolts and the configured PGA gain, modulator digital gain and input range.
+  ///
+  /// Returns `None` if fewer than two results could be used.
+  pub fn measure_noise<R>(
+    &mut self,
+    ready: &mut R,
+    n: u32,
+    reference_uv: u32,
+  ) -> Result<Option<noise::NoiseReport>, Error<E>>
+  where
+    R: DataReady,
+  {
+    let ctrl2 = self.cached_reg_u8::<Ctrl2>()?;
+    let scale = noise::Scale::new(reference_uv, ctrl2.pga(), ctrl2.digital_gain(), self.conversion.range);
+
+    let mut stats = noise::Statistics::new();
+    let mut read = 0;
+    while read < n {
+      let sample = self.wait_sample(ready)?;
+
+      if sample.is_fresh() && !sample.is_collided() {
+        stats.push_sample(&sample);
+        read += 1;
+      }
+    }
+
+    let ConversionSettings { rate, continuous, .. } = self.conversion;
+    Ok(stats.report(&scale, rate.hz(ConversionMode::from_continuous(continuous))))
+  }
+
+  /// Collect conversion results until a triggered capture is complete.
+  ///
+  /// ```rust
+  /// # use embedded_hal::spi::SpiDevice;
+  /// # use max112x::{Conversion, DataReady, Error, Max11214};
+  /// # fn example<SPI: SpiDevice>(adc: &mut Max11214<SPI, Conversion>, ready: &mut impl DataReady) -> Result<(), Error<SPI::Error>> {
+  /// use max112x::capture::{Capture, Trigger};
+  ///
+  /// // Capture 64 results, 16 of them before the result rising above 100.
+  /// let mut capture = Capture::<64>::new(Trigger::Rising(100), 16);
+  /// let record = adc.capture(ready, &mut capture)?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn capture<R, const M: usize>(
+    &mut self,
+    ready: &mut R,
+    capture: &mut capture::Capture<M>,
+  ) -> Result<capture::Record<M>, Error<E>>
+  where
+    R: DataReady,
+  {
+    loop {
+      if let Some(record) = capture.push(self.wait_sample(ready)?) {
+        return Ok(record)
+      }
+    }
+  }
+
+  /// Add `n` conversion results to a code histogram.
+  ///
+  /// Collided results are skipped and not counted.
+  #[cfg(feature = "alloc")]
+  pub fn collect_histogram<R>(
+    &mut self,
+    ready: &mut R,
+    histogram: &mut histogram::Histogram,
+    n: u64,
+  ) -> Result<(), Error<E>>
+  where
+    R: DataReady,
+  {
+    let mut collected = 0;
+    while collected < n {
+      let sample = self.wait_sample(ready)?;
+
+      if sample.is_fresh() && !sample.is_collided() {
+        histogram.push(&sample);
+        collected += 1;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Get the conversion rate.
+  pub fn rate(&self) -> ConversionRate {
+    self.conversion.rate
+  }
+
+  /// Check if the ADC is in continuous conversion mode.
+  pub fn is_continuous(&self) -> bool {
+    self.conversion.continuous
+  }
+
+  /// Change the configuration while converting.
+  ///
+  /// The conversion is paused by switching to standby mode, the changed configuration is applied and the
+  /// conversion is restarted with the previous rate and mode. Conversion results which are not yet fully settled are
+  /// discarded by the methods waiting for results using [`DataReady`], e.g. [`samples`](Self::samples).
+  ///
+  /// If the configuration is not changed, the conversion is not interrupted.
+  ///
+  /// ```rust
+  /// # use embedded_hal::spi::SpiDevice;
+  /// # use max112x::{Conversion, Error, Max11214};
+  /// # fn example<SPI: SpiDevice>(adc: &mut Max11214<SPI, Conversion>) -> Result<(), Error<SPI::Error>> {
+  /// use max112x::Pga;
+  ///
+  /// adc.reconfigure(|config| config.pga = Some(Pga::X2))?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn reconfigure(&mut self, f: impl FnOnce(&mut Config)) -> Result<(), Error<E>> {
+    self.reconfigure_with(f, |_, _| Ok(()))
+  }
+
+  /// Change the configuration while converting, calling `paused` while the conversion is paused.
+  pub(crate) fn reconfigure_with(
+    &mut self,
+    f: impl FnOnce(&mut Config),
+    paused: impl FnOnce(&mut Self, &Config) -> Result<(), Error<E>>,
+  ) -> Result<(), Error<E>> {
+    let old_config = self.read_config()?;
+    let mut config = old_config;
+    f(&mut config);
+
+    if config == old_config {
+      return Ok(())
+    }
+
+    self.standby()?;
+    self.write_config(&config)?;
+    paused(self, &config)?;
+
+    self.resume().map(|_| ())
+  }
+
+  /// Recalibrate while converting.
+  ///
+  /// The conversion is paused by switching to standby mode, the calibration is run and the conversion is restarted
+  /// with the previous rate and mode. Conversion results which are not yet fully settled are discarded by the
+  /// methods waiting for results using [`DataReady`].
+  ///
+  /// Returns the nominal time in nanoseconds from pausing the conversion until the first settled result.
+  pub fn recalibrate(&mut self, calibration: Calibration) -> Result<u64, Error<E>> {
+    let rate = self.conversion.rate;
+    let latency_ns = self.pause_with(|adc| adc.calibrate(calibration, rate))?;
+    Ok(u64::from(calibration.duration_ns()) + latency_ns)
+  }
+
+  /// Pause the conversion, call `paused` and restart the conversion without changing the configuration.
+  ///
+  /// Returns the nominal settling latency in nanoseconds.
+  pub(crate) fn pause_with(&mut self, paused: impl FnOnce(&mut Self) -> Result<(), Error<E>>) -> Result<u64, Error<E>> {
+    self.standby()?;
+    paused(self)?;
+
+    self.resume()
+  }
+
+  /// Restart the paused conversion, marking all results which are not yet fully settled to be discarded.
+  ///
+  /// Returns the nominal settling latency in nanoseconds.
+  fn resume(&mut self) -> Result<u64, Error<E>> {
+    let ConversionSettings { rate, continuous, .. } = self.conversion;
+    let settling = self.convert(rate, continuous)?;
+    self.conversion.unsettled = settling.discard;
+
+    Ok(settling.latency_ns)
+  }
+
+  pub(crate) fn wait_sample<R>(&mut self, ready: &mut R) -> Result<Sample, Error<E>>
+  where
+    R: DataReady,
+  {
+    loop {
+      ready.wait(self)?;
+
+      let sample = self.read_sample()?;
+
+      if !self.skip_sample(&sample) {
+        return Ok(sample)
+      }
+    }
+  }
+
+  /// Check if a sample should be skipped because it is not settled yet.
+  fn skip_sample(&mut self, sample: &Sample) -> bool {
+    if self.conversion.unsettled > 0 && sample.is_fresh() && !sample.is_collided() {
+      self.conversion.unsettled -= 1;
+      return true
+    }
+
+    false
   }
 }
 
@@ -229,65 +1192,50 @@ macro_rules! impl_sleep_standby {
   () => {
     /// Set the system clock source.
     pub fn set_clock(&mut self, clock: ClockSource) -> Result<(), Error<E>> {
-      self.modify_reg_u8(|ctrl1: Ctrl1| match clock {
-        ClockSource::External => ctrl1.union(Ctrl1::EXTCK),
-        ClockSource::Internal => ctrl1.difference(Ctrl1::EXTCK),
-      })
+      self.modify_reg_u8(|ctrl1: Ctrl1| ctrl1.with_clock(clock))
     }
 
     /// Set the bipolar range format.
     pub fn set_format(&mut self, format: Format) -> Result<(), Error<E>> {
-      self.modify_reg_u8(|ctrl1: Ctrl1| match format {
-        Format::OffsetBinary => ctrl1.union(Ctrl1::FORMAT),
-        Format::TwosComplement => ctrl1.difference(Ctrl1::FORMAT),
-      })
+      self.modify_reg_u8(|ctrl1: Ctrl1| ctrl1.with_format(format))
     }
 
     /// Set the PGA gain.
     pub fn set_pga(&mut self, pga: Option<Pga>) -> Result<(), Error<E>> {
-      self.modify_reg_u8(|ctrl2: Ctrl2| {
-        if let Some(pga) = pga {
-          ctrl2.union(Ctrl2::PGAEN).difference(Ctrl2::PGAG).union(Ctrl2::from_bits_truncate(match pga {
-            Pga::X1 => 0b000,
-            Pga::X2 => 0b001,
-            Pga::X4 => 0b010,
-            Pga::X8 => 0b011,
-            Pga::X16 => 0b100,
-            Pga::X32 => 0b101,
-            Pga::X64 => 0b110,
-            Pga::X128 => 0b111,
-          }))
-        } else {
-          ctrl2.difference(Ctrl2::PGAEN)
-        }
-      })
+      self.modify_reg_u8(|ctrl2: Ctrl2| ctrl2.with_pga(pga))
+    }
+
+    /// Apply a configuration.
+    ///
+    /// Only registers whose value changes are written.
+    pub fn apply_config(&mut self, config: &Config) -> Result<(), Error<E>> {
+      self.write_config(config)
     }
 
     /// Run a self-calibration.
     pub fn self_calibrate(&mut self, calibration: Calibration) -> Result<(), Error<E>> {
-      let mut duration = 0;
-
-      self.modify_reg_u8(|ctrl1: Ctrl5| match calibration {
-        Calibration::SelfCalibration => {
-          duration = 200_000_000;
-          ctrl1.difference(Ctrl5::CAL)
-        },
-        Calibration::SystemOffsetCalibration => {
-          duration = 100_000_000;
-          ctrl1.difference(Ctrl5::CAL1).union(Ctrl5::CAL0)
-        },
-        Calibration::SystemFullScaleCalibration => {
-          duration = 100_000_000;
-          ctrl1.union(Ctrl5::CAL1).difference(Ctrl5::CAL0)
-        },
-      })?;
-
-      self
-        .spi
-        .transaction(&mut [Operation::Write(&[Command::calibrate().bits()]), Operation::DelayNs(duration)])
-        .map_err(|err| Error::Spi(err))?;
-
-      Ok(())
+      self.calibrate(calibration, ConversionRate::Hz0_95)
+    }
+
+    /// Run a self-calibration for every combination of PGA gain and conversion rate and store the results in the
+    /// calibration table.
+    ///
+    /// The PGA gain is left at the last combination. Returns the number of combinations which did not fit into the
+    /// table.
+    pub fn calibrate_table(&mut self, settings: &[(Option<Pga>, ConversionRate)]) -> Result<usize, Error<E>> {
+      let mut rejected = 0;
+
+      for &(pga, rate) in settings {
+        self.set_pga(pga)?;
+        self.calibrate(Calibration::SelfCalibration, rate)?;
+
+        let data = self.read_calibration()?;
+        if !self.calibration_table.insert(pga, rate, data) {
+          rejected += 1;
+        }
+      }
+
+      Ok(rejected)
     }
 
     /// Get the system offset calibration value.
@@ -313,17 +1261,47 @@ macro_rules! impl_sleep_standby {
       let soc_adc = self.read_reg_u24::<ScgcAdc>()?;
       Ok(soc_adc.0.into())
     }
+
+    /// Read the calibration coefficients determined by the last calibrations.
+    pub fn read_calibration(&mut self) -> Result<CalibrationData, Error<E>> {
+      Ok(CalibrationData {
+        system_offset: self.read_reg_u24::<SocAdc>()?.0.into(),
+        system_gain: self.read_reg_u24::<SgcAdc>()?.0.into(),
+        self_offset: self.read_reg_u24::<ScocAdc>()?.0.into(),
+        self_gain: self.read_reg_u24::<ScgcAdc>()?.0.into(),
+      })
+    }
+
+    /// Write back all writable registers from a snapshot.
+    ///
+    /// The calibration and highpass filter registers are written first, followed by `CTRL5` to `CTRL2`.
+    /// `CTRL1` is written last and keeps the current power-down bits, so the ADC stays in its current mode.
+    pub fn restore_registers(&mut self, snapshot: &RegisterSnapshot) -> Result<(), Error<E>> {
+      self.loaded_calibration = None;
+      self.write_reg_u24(snapshot.soc_spi)?;
+      self.write_reg_u24(snapshot.sgc_spi)?;
+      self.write_reg_u24(snapshot.scoc_spi)?;
+      self.write_reg_u24(snapshot.scgc_spi)?;
+      self.write_reg_u16(snapshot.hpf)?;
+
+      self.write_reg_u8(snapshot.ctrl5)?;
+      self.write_reg_u8(snapshot.ctrl4)?;
+      self.write_reg_u8(snapshot.ctrl3)?;
+      self.write_reg_u8(snapshot.ctrl2)?;
+
+      self.modify_reg_u8(|ctrl1: Ctrl1| snapshot.ctrl1.difference(Ctrl1::PD).union(ctrl1.intersection(Ctrl1::PD)))
+    }
   };
 }
 
-impl<SPI, E> Max11214<SPI, Sleep>
+impl<SPI, E, const N: usize> Max11214<SPI, Sleep, N>
 where
   SPI: SpiDevice<u8, Error = E>,
 {
   impl_sleep_standby!();
 }
 
-impl<SPI, E> Max11214<SPI, Standby>
+impl<SPI, E, const N: usize> Max11214<SPI, Standby, N>
 where
   SPI: SpiDevice<u8, Error = E>,
 {
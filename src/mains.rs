@@ -0,0 +1,95 @@
+//! Selection of conversion settings for mains frequency rejection.
+
+use crate::{
+  filter_response::{self, to_db},
+  ConversionMode, ConversionRate, Filter,
+};
+
+/// Relative deviation of the mains frequency from its nominal value which is taken into account.
+pub const MAINS_TOLERANCE: f32 = 0.01;
+
+const TOLERANCE_STEPS: u16 = 20;
+
+/// Maximum number of candidates returned by [`rank`].
+pub const MAX_CANDIDATES: usize = ConversionRate::ALL.len() * 3;
+
+/// A combination of conversion settings with its predicted mains rejection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candidate {
+  /// Conversion rate to pass to `start_conversion`.
+  pub rate: ConversionRate,
+  /// Conversion mode to pass to `start_conversion`.
+  pub mode: ConversionMode,
+  /// Digital filter.
+  pub filter: Filter,
+  /// Worst-case rejection in decibels within the mains frequency tolerance.
+  pub rejection_db: f32,
+}
+
+impl Candidate {
+  /// Check if this candidate uses continuous conversion.
+  pub const fn continuous(&self) -> bool {
+    matches!(self.mode, ConversionMode::Continuous)
+  }
+}
+
+/// Get the worst-case rejection in decibels of the given settings for a mains frequency.
+pub fn rejection_db(rate: ConversionRate, filter: Filter, mode: ConversionMode, mains_hz: f32) -> f32 {
+  let worst = (0..=TOLERANCE_STEPS)
+    .map(|i| {
+      let deviation = MAINS_TOLERANCE * (2.0 * f32::from(i) / f32::from(TOLERANCE_STEPS) - 1.0);
+      filter_response::magnitude(rate, filter, mode, mains_hz * (1.0 + deviation))
+    })
+    .fold(0.0, f32::max);
+
+  -to_db(worst)
+}
+
+/// Rank all supported conversion settings with at least the given throughput by their mains rejection.
+///
+/// The candidates are written to `candidates`, best first. Returns the number of candidates written, which is at
+/// most [`MAX_CANDIDATES`].
+pub fn rank(mains_hz: f32, min_rate_hz: f32, candidates: &mut [Candidate]) -> usize {
+  let mut len = 0;
+
+  for rate in ConversionRate::iter() {
+    for mode in [ConversionMode::Continuous, ConversionMode::SingleCycle] {
+      if rate.hz(mode).to_f32() < min_rate_hz {
+        continue
+      }
+
+      for filter in [Filter::Sinc, Filter::Fir] {
+        if !filter_response::is_supported(rate, filter, mode) {
+          continue
+        }
+
+        let Some(candidate) = candidates.get_mut(len) else { break };
+        *candidate = Candidate { rate, mode, filter, rejection_db: rejection_db(rate, filter, mode, mains_hz) };
+        len += 1;
+      }
+    }
+  }
+
+  candidates[..len].sort_unstable_by(|a, b| b.rejection_db.total_cmp(&a.rejection_db));
+  len
+}
+
+/// Find the conversion settings with the best mains rejection and at least the given throughput.
+///
+/// ```rust
+/// use max112x::mains;
+///
+/// let best = mains::best(50.0, 10.0).unwrap();
+/// assert!(best.rejection_db > 60.0);
+/// ```
+pub fn best(mains_hz: f32, min_rate_hz: f32) -> Option<Candidate> {
+  let mut candidates = [Candidate {
+    rate: ConversionRate::Hz0_95,
+    mode: ConversionMode::Continuous,
+    filter: Filter::Sinc,
+    rejection_db: 0.0,
+  }; MAX_CANDIDATES];
+
+  let len = rank(mains_hz, min_rate_hz, &mut candidates);
+  candidates[..len].first().copied()
+}
@@ -0,0 +1,235 @@
+//! Noise analysis of conversion results.
+
+use crate::{DigitalGain, Hertz, Pga, Range, Sample};
+
+/// Number of codes spanning the full-scale range.
+const FULL_SCALE_CODES: f64 = (1u32 << 24) as f64;
+
+/// Conversion of codes into microvolts.
+///
+/// ```rust
+/// use max112x::{noise::Scale, DigitalGain, Pga, Range};
+///
+/// let scale = Scale::new(2_500_000, Some(Pga::X4), DigitalGain::X2, Range::Bipolar);
+/// assert_eq!(scale.to_uv(0x800000), 312_500.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scale {
+  lsb_uv: f64,
+}
+
+impl Scale {
+  /// Create a scale for the given reference voltage in microvolts, PGA gain, modulator digital gain and input range.
+  pub fn new(reference_uv: u32, pga: Option<Pga>, digital_gain: DigitalGain, range: Range) -> Self {
+    let gain = f64::from(pga.map_or(1, Pga::gain)) * f64::from(digital_gain.gain());
+    let span_uv = match range {
+      Range::Bipolar => 2.0 * f64::from(reference_uv),
+      Range::Unipolar => f64::from(reference_uv),
+    };
+
+    Self { lsb_uv: span_uv / gain / FULL_SCALE_CODES }
+  }
+
+  /// Get the size of one code in microvolts.
+  pub fn lsb_uv(&self) -> f64 {
+    self.lsb_uv
+  }
+
+  /// Convert a conversion result into microvolts.
+  pub fn to_uv(&self, value: i32) -> f64 {
+    f64::from(value) * self.lsb_uv
+  }
+}
+
+/// Running statistics of conversion results.
+///
+/// The mean and variance are accumulated using Welford's algorithm, so no results need to be stored.
+///
+/// ```rust
+/// use max112x::{
+///   noise::{Scale, Statistics},
+///   DigitalGain, Hertz, Range,
+/// };
+///
+/// let mut stats = Statistics::new();
+/// for value in [-2, 0, 1, 3, -1, 0, 2, -3] {
+///   stats.push(value);
+/// }
+///
+/// assert_eq!(stats.peak_to_peak(), 6);
+///
+/// let scale = Scale::new(2_500_000, None, DigitalGain::X1, Range::Bipolar);
+/// let report = stats.report(&scale, Hertz::new(1000, 1)).unwrap();
+/// assert!(report.noise_free_bits > 21.0 && report.noise_free_bits < 22.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statistics {
+  count: u32,
+  mean: f64,
+  m2: f64,
+  min: i32,
+  max: i32,
+}
+
+impl Statistics {
+  /// Create empty statistics.
+  pub const fn new() -> Self {
+    Self { count: 0, mean: 0.0, m2: 0.0, min: i32::MAX, max: i32::MIN }
+  }
+
+  /// Add a conversion result.
+  pub fn push(&mut self, value: i32) {
+    self.count += 1;
+
+    let x = f64::from(value);
+    let delta = x - self.mean;
+    self.mean += delta / f64::from(self.count);
+    self.m2 += delta * (x - self.mean);
+
+    self.min = self.min.min(value);
+    self.max = self.max.max(value);
+  }
+
+  /// Add a sample if it is [valid](Sample::is_valid).
+  ///
+  /// Returns whether the sample was added.
+  pub fn push_sample(&mut self, sample: &Sample) -> bool {
+    if sample.is_valid() {
+      self.push(sample.value());
+    }
+
+    sample.is_valid()
+  }
+
+  /// Remove all results.
+  pub fn reset(&mut self) {
+    *self = Self::new();
+  }
+
+  /// Get the number of results.
+  pub const fn count(&self) -> u32 {
+    self.count
+  }
+
+  /// Get the mean in codes.
+  pub const fn mean(&self) -> f64 {
+    self.mean
+  }
+
+  /// Get the sample variance in codes squared.
+  pub fn variance(&self) -> f64 {
+    match self.count {
+      0 | 1 => 0.0,
+      n => self.m2 / f64::from(n - 1),
+    }
+  }
+
+  /// Get the RMS noise, i.e. the standard deviation, in codes.
+  pub fn rms_noise(&self) -> f64 {
+    libm::sqrt(self.variance())
+  }
+
+  /// Get the smallest result, if any.
+  pub const fn min(&self) -> Option<i32> {
+    if self.count == 0 {
+      None
+    } else {
+      Some(self.min)
+    }
+  }
+
+  /// Get the largest result, if any.
+  pub const fn max(&self) -> Option<i32> {
+    if self.count == 0 {
+      None
+    } else {
+      Some(self.max)
+    }
+  }
+
+  /// Get the peak-to-peak noise in codes.
+  pub const fn peak_to_peak(&self) -> u32 {
+    if self.count == 0 {
+      0
+    } else {
+      self.max.abs_diff(self.min)
+    }
+  }
+
+  /// Get the effective resolution in bits, i.e. the full-scale range relative to the RMS noise.
+  pub fn effective_resolution(&self) -> f32 {
+    resolution_bits(self.rms_noise())
+  }
+
+  /// Get the noise-free resolution in bits, i.e. the full-scale range relative to the peak-to-peak noise.
+  pub fn noise_free_bits(&self) -> f32 {
+    resolution_bits(f64::from(self.peak_to_peak()))
+  }
+
+  /// Summarize the statistics in codes and microvolts.
+  ///
+  /// The noise density is derived from the RMS noise assuming white noise over the Nyquist bandwidth of the given
+  /// conversion rate. Returns `None` if there are fewer than two results, since no noise can be determined.
+  pub fn report(&self, scale: &Scale, rate: Hertz) -> Option<NoiseReport> {
+    if self.count < 2 {
+      return None
+    }
+
+    let rms_noise = self.rms_noise();
+    let peak_to_peak = self.peak_to_peak();
+    let nyquist_hz = f64::from(rate.to_f32()) / 2.0;
+
+    Some(NoiseReport {
+      count: self.count,
+      mean: self.mean as f32,
+      rms_noise: rms_noise as f32,
+      peak_to_peak,
+      mean_uv: (self.mean * scale.lsb_uv()) as f32,
+      rms_noise_uv: (rms_noise * scale.lsb_uv()) as f32,
+      peak_to_peak_uv: (f64::from(peak_to_peak) * scale.lsb_uv()) as f32,
+      noise_density_uv: (rms_noise * scale.lsb_uv() / libm::sqrt(nyquist_hz)) as f32,
+      effective_resolution: self.effective_resolution(),
+      noise_free_bits: self.noise_free_bits(),
+    })
+  }
+}
+
+impl Default for Statistics {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Summary of a noise measurement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoiseReport {
+  /// Number of results.
+  pub count: u32,
+  /// Mean in codes.
+  pub mean: f32,
+  /// RMS noise in codes.
+  pub rms_noise: f32,
+  /// Peak-to-peak noise in codes.
+  pub peak_to_peak: u32,
+  /// Mean in microvolts.
+  pub mean_uv: f32,
+  /// RMS noise in microvolts.
+  pub rms_noise_uv: f32,
+  /// Peak-to-peak noise in microvolts.
+  pub peak_to_peak_uv: f32,
+  /// Noise density in µV/√Hz.
+  pub noise_density_uv: f32,
+  /// Effective resolution in bits.
+  pub effective_resolution: f32,
+  /// Noise-free resolution in bits.
+  pub noise_free_bits: f32,
+}
+
+/// Get the resolution in bits for the given noise in codes, limited to 24 bits.
+fn resolution_bits(noise: f64) -> f32 {
+  if noise <= 1.0 {
+    return 24.0
+  }
+
+  libm::log2(FULL_SCALE_CODES / noise) as f32
+}
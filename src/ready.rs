@@ -0,0 +1,88 @@
+use embedded_hal::{
+  delay::DelayNs,
+  digital::{Error as _, InputPin},
+  spi::SpiDevice,
+};
+
+use crate::{Conversion, Error, Max11214, Sample};
+
+/// A way of waiting for a new conversion result.
+pub trait DataReady {
+  /// Wait until a new conversion result is available.
+  fn wait<SPI, const N: usize>(&mut self, adc: &mut Max11214<SPI, Conversion, N>) -> Result<(), Error<SPI::Error>>
+  where
+    SPI: SpiDevice<u8>;
+}
+
+/// Wait for a new conversion result using the `RDYB` pin.
+#[derive(Debug)]
+pub struct ReadyPin<P>(pub P);
+
+impl<P> DataReady for ReadyPin<P>
+where
+  P: InputPin,
+{
+  fn wait<SPI, const N: usize>(&mut self, _adc: &mut Max11214<SPI, Conversion, N>) -> Result<(), Error<SPI::Error>>
+  where
+    SPI: SpiDevice<u8>,
+  {
+    while self.0.is_high().map_err(|err| Error::Pin(err.kind()))? {}
+    Ok(())
+  }
+}
+
+/// Wait for a new conversion result by polling the status register.
+#[derive(Debug)]
+pub struct PollStatus<D> {
+  delay: D,
+  interval_ns: u32,
+}
+
+impl<D> PollStatus<D> {
+  /// Poll the status register, waiting for `interval_ns` nanoseconds between reads.
+  pub const fn new(delay: D, interval_ns: u32) -> Self {
+    Self { delay, interval_ns }
+  }
+
+  /// Release the contained delay.
+  pub fn release(self) -> D {
+    self.delay
+  }
+}
+
+impl<D> DataReady for PollStatus<D>
+where
+  D: DelayNs,
+{
+  fn wait<SPI, const N: usize>(&mut self, adc: &mut Max11214<SPI, Conversion, N>) -> Result<(), Error<SPI::Error>>
+  where
+    SPI: SpiDevice<u8>,
+  {
+    while !adc.status()?.data_ready() {
+      self.delay.delay_ns(self.interval_ns);
+    }
+
+    Ok(())
+  }
+}
+
+/// An iterator over conversion results.
+///
+/// Created by [`Max11214::samples`].
+#[derive(Debug)]
+pub struct Samples<'a, SPI, R, const N: usize = 0> {
+  pub(crate) adc: &'a mut Max11214<SPI, Conversion, N>,
+  pub(crate) ready: &'a mut R,
+}
+
+impl<SPI, R, const N: usize> Iterator for Samples<'_, SPI, R, N>
+where
+  SPI: SpiDevice<u8>,
+  R: DataReady,
+{
+  type Item = Result<Sample, Error<SPI::Error>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    Some(self.adc.wait_sample(self.ready))
+  }
+}
@@ -0,0 +1,167 @@
+use embedded_hal::spi::SpiDevice;
+
+use crate::{Calibration, DataReady, Error, Timebase, Timestamped, TimestampedSample};
+
+/// A source of temperature readings.
+pub trait Thermometer {
+  /// Get the current temperature in millidegrees Celsius, or `None` if it is not available.
+  fn temperature_mc(&mut self) -> Option<i32>;
+}
+
+/// No temperature readings, only recalibrate periodically.
+impl Thermometer for () {
+  fn temperature_mc(&mut self) -> Option<i32> {
+    None
+  }
+}
+
+/// Schedule for recalibrating periodically or when the temperature changes.
+///
+/// ```rust
+/// use max112x::{Calibration, RecalibrationSchedule};
+///
+/// let mut schedule = RecalibrationSchedule::new(Calibration::SelfCalibration)
+///   .interval_minutes(10)
+///   .temperature_threshold_mc(2_000);
+///
+/// assert!(!schedule.poll(0, Some(25_000)));
+/// assert!(schedule.poll(1_000_000_000, Some(27_500)));
+/// assert!(!schedule.poll(2_000_000_000, Some(27_000)));
+/// assert!(schedule.poll(601_000_000_000, Some(27_000)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecalibrationSchedule {
+  calibration: Calibration,
+  interval_ns: Option<u64>,
+  threshold_mc: Option<u32>,
+  last_ns: Option<u64>,
+  last_temperature_mc: Option<i32>,
+}
+
+impl RecalibrationSchedule {
+  /// Create a schedule running the given calibration, which is never due until an interval or a temperature
+  /// threshold is set.
+  ///
+  /// Use [`Calibration::SelfCalibration`] to correct offset and gain, or [`Calibration::SystemOffsetCalibration`]
+  /// to correct only the offset while the input is shorted.
+  pub const fn new(calibration: Calibration) -> Self {
+    Self { calibration, interval_ns: None, threshold_mc: None, last_ns: None, last_temperature_mc: None }
+  }
+
+  /// Recalibrate after the given time in nanoseconds.
+  pub const fn interval_ns(mut self, interval_ns: u64) -> Self {
+    self.interval_ns = Some(interval_ns);
+    self
+  }
+
+  /// Recalibrate after the given time in minutes.
+  pub const fn interval_minutes(self, minutes: u32) -> Self {
+    self.interval_ns(minutes as u64 * 60_000_000_000)
+  }
+
+  /// Recalibrate when the temperature changes by more than the given amount in millidegrees Celsius.
+  pub const fn temperature_threshold_mc(mut self, threshold_mc: u32) -> Self {
+    self.threshold_mc = Some(threshold_mc);
+    self
+  }
+
+  /// Get the calibration which is run.
+  pub const fn calibration(&self) -> Calibration {
+    self.calibration
+  }
+
+  /// Check if a recalibration is due at the given time and temperature.
+  ///
+  /// The first call only records the reference time and temperature. When returning `true`, the given time and
+  /// temperature become the new reference, so the caller is expected to recalibrate.
+  pub fn poll(&mut self, now_ns: u64, temperature_mc: Option<i32>) -> bool {
+    let Some(last_ns) = self.last_ns else {
+      self.last_ns = Some(now_ns);
+      self.last_temperature_mc = temperature_mc;
+      return false
+    };
+
+    let interval_elapsed = self.interval_ns.is_some_and(|interval_ns| now_ns.saturating_sub(last_ns) >= interval_ns);
+
+    let temperature_changed = match (self.threshold_mc, self.last_temperature_mc, temperature_mc) {
+      (Some(threshold_mc), Some(last_mc), Some(mc)) => last_mc.abs_diff(mc) > threshold_mc,
+      _ => false,
+    };
+
+    if self.last_temperature_mc.is_none() {
+      self.last_temperature_mc = temperature_mc;
+    }
+
+    if !interval_elapsed && !temperature_changed {
+      return false
+    }
+
+    self.last_ns = Some(now_ns);
+    self.last_temperature_mc = temperature_mc.or(self.last_temperature_mc);
+    true
+  }
+}
+
+impl<'a, SPI, R, T, const N: usize> Timestamped<'a, SPI, R, T, N> {
+  /// Recalibrate according to the given schedule between conversion results.
+  ///
+  /// The schedule is checked using the timestamp of each result. The conversion results which would have been
+  /// produced during a recalibration are counted as missed, so the first result after a recalibration is marked as a
+  /// [discontinuity](TimestampedSample::is_discontinuity) and the indices keep following the nominal conversion rate.
+  pub fn recalibrating<H>(self, schedule: RecalibrationSchedule, thermometer: H) -> Recalibrating<'a, SPI, R, T, H, N>
+  where
+    H: Thermometer,
+  {
+    Recalibrating { samples: self, schedule, thermometer, due: false }
+  }
+}
+
+/// An iterator over timestamped conversion results with scheduled recalibrations.
+///
+/// Created by [`Timestamped::recalibrating`].
+#[derive(Debug)]
+pub struct Recalibrating<'a, SPI, R, T, H, const N: usize = 0> {
+  samples: Timestamped<'a, SPI, R, T, N>,
+  schedule: RecalibrationSchedule,
+  thermometer: H,
+  due: bool,
+}
+
+impl<SPI, R, T, H, const N: usize> Recalibrating<'_, SPI, R, T, H, N> {
+  /// Get the recalibration schedule.
+  pub fn schedule(&self) -> &RecalibrationSchedule {
+    &self.schedule
+  }
+}
+
+impl<SPI, R, T, H, const N: usize> Iterator for Recalibrating<'_, SPI, R, T, H, N>
+where
+  SPI: SpiDevice<u8>,
+  R: DataReady,
+  T: Timebase,
+  H: Thermometer,
+{
+  type Item = Result<TimestampedSample, Error<SPI::Error>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.due {
+      self.due = false;
+
+      let gap_ns = match self.samples.samples.adc.recalibrate(self.schedule.calibration) {
+        Ok(gap_ns) => gap_ns,
+        Err(err) => return Some(Err(err)),
+      };
+
+      self.samples.skipped += (gap_ns / self.samples.period_ns).max(1);
+    }
+
+    let sample = match self.samples.next()? {
+      Ok(sample) => sample,
+      Err(err) => return Some(Err(err)),
+    };
+
+    self.due = self.schedule.poll(sample.timestamp_ns, self.thermometer.temperature_mc());
+
+    Some(Ok(sample))
+  }
+}
@@ -1,6 +1,9 @@
 #![allow(clippy::unusual_byte_groupings)] // FIXME: https://github.com/rust-lang/rust-clippy/issues/9183
 
-use crate::{types::u24, ConversionRate};
+use crate::{
+  types::u24, Calibration, ClockSource, ConversionRate, DigitalGain, Filter, FirPhase, Format, Gpio, Pga, Range,
+  SyncMode,
+};
 
 pub trait ReadReg<R>
 where
@@ -15,6 +18,24 @@ pub trait WriteReg<R>: ReadReg<R> {
   fn to_reg(self) -> R;
 }
 
+/// Get the bits of a written 8-bit register value which are expected to read back unchanged.
+pub fn readback_mask(addr: u8, value: u8) -> u8 {
+  match addr {
+    // The `DIO` bits of inputs reflect the pin state.
+    <Ctrl4 as ReadReg<u8>>::ADDR => {
+      let ctrl4 = Ctrl4::from_bits_truncate(value);
+      let mut mask = Ctrl4::all();
+      for (dir, dio) in Ctrl4::PINS {
+        if !ctrl4.contains(dir) {
+          mask.remove(dio);
+        }
+      }
+      mask.bits()
+    },
+    _ => 0xFF,
+  }
+}
+
 macro_rules! register {
   (@impl_read_reg $Reg:ident : $addr:literal : $RegTy:ty) => {
     impl ReadReg<$RegTy> for $Reg {
@@ -38,6 +59,7 @@ macro_rules! register {
     $vis:vis struct $Reg:ident($RegTy:ty): $addr:literal;
   ) => {
     #[doc = concat!($name, " register (`", stringify!($addr), "`)")]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     $vis struct $Reg(pub(crate) $RegTy);
 
     impl $Reg {
@@ -169,6 +191,70 @@ register! {
     /// - 0 Use internal oscillator as the system clock.
     /// - 1 Use external clock as the system clock.
     const EXTCK  = 0b10000000;
+
+    const PD = Self::PD1.bits() | Self::PD0.bits();
+  }
+}
+
+impl Ctrl1 {
+  pub const fn clock(self) -> ClockSource {
+    if self.contains(Self::EXTCK) {
+      ClockSource::External
+    } else {
+      ClockSource::Internal
+    }
+  }
+
+  pub const fn with_clock(self, clock: ClockSource) -> Self {
+    match clock {
+      ClockSource::External => self.union(Self::EXTCK),
+      ClockSource::Internal => self.difference(Self::EXTCK),
+    }
+  }
+
+  pub const fn sync(self) -> SyncMode {
+    if self.contains(Self::SYNC) {
+      SyncMode::Continuous
+    } else {
+      SyncMode::Pulse
+    }
+  }
+
+  pub const fn with_sync(self, sync: SyncMode) -> Self {
+    match sync {
+      SyncMode::Continuous => self.union(Self::SYNC),
+      SyncMode::Pulse => self.difference(Self::SYNC),
+    }
+  }
+
+  pub const fn range(self) -> Range {
+    if self.contains(Self::UB) {
+      Range::Unipolar
+    } else {
+      Range::Bipolar
+    }
+  }
+
+  pub const fn with_range(self, range: Range) -> Self {
+    match range {
+      Range::Unipolar => self.union(Self::UB),
+      Range::Bipolar => self.difference(Self::UB),
+    }
+  }
+
+  pub const fn format(self) -> Format {
+    if self.contains(Self::FORMAT) {
+      Format::OffsetBinary
+    } else {
+      Format::TwosComplement
+    }
+  }
+
+  pub const fn with_format(self, format: Format) -> Self {
+    match format {
+      Format::OffsetBinary => self.union(Self::FORMAT),
+      Format::TwosComplement => self.difference(Self::FORMAT),
+    }
   }
 }
 
@@ -218,6 +304,62 @@ register! {
   }
 }
 
+impl Ctrl2 {
+  pub const fn digital_gain(self) -> DigitalGain {
+    match self.intersection(Self::DGAIN).bits() >> 6 {
+      0b00 => DigitalGain::X1,
+      0b01 => DigitalGain::X2,
+      0b10 => DigitalGain::X4,
+      0b11 => DigitalGain::X8,
+      _ => unreachable!(),
+    }
+  }
+
+  pub const fn with_digital_gain(self, gain: DigitalGain) -> Self {
+    let bits = match gain {
+      DigitalGain::X1 => 0b00,
+      DigitalGain::X2 => 0b01,
+      DigitalGain::X4 => 0b10,
+      DigitalGain::X8 => 0b11,
+    };
+
+    self.difference(Self::DGAIN).union(Self::from_bits_truncate(bits << 6))
+  }
+
+  pub const fn pga(self) -> Option<Pga> {
+    if !self.contains(Self::PGAEN) {
+      return None
+    }
+
+    Some(match self.intersection(Self::PGAG).bits() {
+      0b000 => Pga::X1,
+      0b001 => Pga::X2,
+      0b010 => Pga::X4,
+      0b011 => Pga::X8,
+      0b100 => Pga::X16,
+      0b101 => Pga::X32,
+      0b110 => Pga::X64,
+      0b111 => Pga::X128,
+      _ => unreachable!(),
+    })
+  }
+
+  pub const fn with_pga(self, pga: Option<Pga>) -> Self {
+    let Some(pga) = pga else { return self.difference(Self::PGAEN) };
+
+    self.union(Self::PGAEN).difference(Self::PGAG).union(Self::from_bits_truncate(match pga {
+      Pga::X1 => 0b000,
+      Pga::X2 => 0b001,
+      Pga::X4 => 0b010,
+      Pga::X8 => 0b011,
+      Pga::X16 => 0b100,
+      Pga::X32 => 0b101,
+      Pga::X64 => 0b110,
+      Pga::X128 => 0b111,
+    }))
+  }
+}
+
 register! {
   /// Control 3 Register (`CTRL3`)
   pub struct Ctrl3: 0x3: u8 {
@@ -227,6 +369,46 @@ register! {
     const PHASE   = 0b00000100;
     const FILT1   = 0b00000010;
     const FILT0   = 0b00000001;
+
+    const FILT = Self::FILT1.bits() | Self::FILT0.bits();
+  }
+}
+
+impl Ctrl3 {
+  pub const fn filter(self) -> Filter {
+    match self.intersection(Self::FILT).bits() {
+      0b00 => Filter::Fir,
+      0b01 => Filter::FirHighpass,
+      0b10 => Filter::Sinc,
+      0b11 => Filter::SincHighpass,
+      _ => unreachable!(),
+    }
+  }
+
+  pub const fn with_filter(self, filter: Filter) -> Self {
+    let bits = match filter {
+      Filter::Fir => 0b00,
+      Filter::FirHighpass => 0b01,
+      Filter::Sinc => 0b10,
+      Filter::SincHighpass => 0b11,
+    };
+
+    self.difference(Self::FILT).union(Self::from_bits_truncate(bits))
+  }
+
+  pub const fn fir_phase(self) -> FirPhase {
+    if self.contains(Self::PHASE) {
+      FirPhase::Minimum
+    } else {
+      FirPhase::Linear
+    }
+  }
+
+  pub const fn with_fir_phase(self, phase: FirPhase) -> Self {
+    match phase {
+      FirPhase::Minimum => self.union(Self::PHASE),
+      FirPhase::Linear => self.difference(Self::PHASE),
+    }
   }
 }
 
@@ -242,6 +424,46 @@ register! {
   }
 }
 
+impl Ctrl4 {
+  const PINS: [(Self, Self); 3] = [(Self::DIR1, Self::DIO1), (Self::DIR2, Self::DIO2), (Self::DIR3, Self::DIO3)];
+
+  pub const fn gpio(self) -> [Gpio; 3] {
+    let mut gpio = [Gpio::Input; 3];
+
+    let mut i = 0;
+    while i < gpio.len() {
+      let (dir, dio) = Self::PINS[i];
+
+      if self.contains(dir) {
+        gpio[i] = if self.contains(dio) { Gpio::OutputHigh } else { Gpio::OutputLow };
+      }
+
+      i += 1;
+    }
+
+    gpio
+  }
+
+  pub const fn with_gpio(self, gpio: [Gpio; 3]) -> Self {
+    let mut ctrl4 = self;
+
+    let mut i = 0;
+    while i < gpio.len() {
+      let (dir, dio) = Self::PINS[i];
+
+      ctrl4 = match gpio[i] {
+        Gpio::Input => ctrl4.difference(dir),
+        Gpio::OutputLow => ctrl4.union(dir).difference(dio),
+        Gpio::OutputHigh => ctrl4.union(dir).union(dio),
+      };
+
+      i += 1;
+    }
+
+    ctrl4
+  }
+}
+
 register! {
   /// Control 5 Register (`CTRL5`)
   pub struct Ctrl5: 0x5: u8 {
@@ -256,6 +478,17 @@ register! {
   }
 }
 
+impl Ctrl5 {
+  pub const fn calibration(self) -> Option<Calibration> {
+    match self.intersection(Self::CAL).bits() >> 6 {
+      0b00 => Some(Calibration::SelfCalibration),
+      0b01 => Some(Calibration::SystemOffsetCalibration),
+      0b10 => Some(Calibration::SystemFullScaleCalibration),
+      _ => None,
+    }
+  }
+}
+
 register! {
   /// 32-bit Data Register (`DATA`)
   pub struct Data32(u32): 0x6;
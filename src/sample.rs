@@ -0,0 +1,75 @@
+use crate::{Format, Range, Status};
+
+/// A conversion result together with the status at the time it was read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+  pub(crate) code: u32,
+  pub(crate) value: i32,
+  pub(crate) status: Status,
+  pub(crate) read_error: bool,
+}
+
+impl Sample {
+  pub(crate) const fn new(code: u32, range: Range, format: Format, status: Status, read_error: bool) -> Self {
+    Self { code, value: decode(code, range, format), status, read_error }
+  }
+
+  /// Replace the conversion result with a processed value.
+  pub(crate) const fn with_value(self, value: i32) -> Self {
+    Self { value, ..self }
+  }
+
+  /// Get the raw 24-bit code from the DATA register.
+  pub const fn code(&self) -> u32 {
+    self.code
+  }
+
+  /// Get the conversion result as a signed value.
+  ///
+  /// Bipolar results are in the range `-0x800000..=0x7FFFFF` regardless of the configured format, unipolar results
+  /// are in the range `0..=0xFFFFFF`.
+  pub const fn value(&self) -> i32 {
+    self.value
+  }
+
+  /// Get the status read before the DATA register.
+  pub const fn status(&self) -> Status {
+    self.status
+  }
+
+  /// Check if this is a new conversion result, i.e. it was not returned by a previous read.
+  pub const fn is_fresh(&self) -> bool {
+    self.status.data_ready()
+  }
+
+  /// Check if the conversion result was clipped.
+  pub const fn is_clipped(&self) -> bool {
+    self.status.data_overrange()
+  }
+
+  /// Check if the analog input exceeded 1.3 × full-scale range.
+  pub const fn is_analog_overrange(&self) -> bool {
+    self.status.analog_overrange()
+  }
+
+  /// Check if a new result was written to the DATA register while it was being read.
+  pub const fn is_collided(&self) -> bool {
+    self.read_error
+  }
+
+  /// Check if this is a fresh conversion result which was neither clipped nor collided.
+  pub const fn is_valid(&self) -> bool {
+    self.is_fresh() && !self.is_clipped() && !self.is_analog_overrange() && !self.is_collided()
+  }
+}
+
+/// Decode a raw 24-bit code into a signed value.
+pub(crate) const fn decode(code: u32, range: Range, format: Format) -> i32 {
+  let code = code & 0xFFFFFF;
+
+  match (range, format) {
+    (Range::Unipolar, _) => code as i32,
+    (Range::Bipolar, Format::TwosComplement) => ((code << 8) as i32) >> 8,
+    (Range::Bipolar, Format::OffsetBinary) => code as i32 - 0x800000,
+  }
+}
@@ -0,0 +1,92 @@
+use core::fmt;
+
+use crate::{register::*, Config, Status};
+
+/// A snapshot of all ADC registers.
+///
+/// Created by [`Max11214::dump_registers`](crate::Max11214::dump_registers) and written back using
+/// [`Max11214::restore_registers`](crate::Max11214::restore_registers).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct RegisterSnapshot {
+  pub(crate) stat: Stat,
+  pub(crate) ctrl1: Ctrl1,
+  pub(crate) ctrl2: Ctrl2,
+  pub(crate) ctrl3: Ctrl3,
+  pub(crate) ctrl4: Ctrl4,
+  pub(crate) ctrl5: Ctrl5,
+  pub(crate) data: u32,
+  pub(crate) soc_spi: SocSpi,
+  pub(crate) sgc_spi: SgcSpi,
+  pub(crate) scoc_spi: ScocSpi,
+  pub(crate) scgc_spi: ScgcSpi,
+  pub(crate) hpf: Hpf,
+  pub(crate) soc_adc: SocAdc,
+  pub(crate) sgc_adc: SgcAdc,
+  pub(crate) scoc_adc: ScocAdc,
+  pub(crate) scgc_adc: ScgcAdc,
+}
+
+impl RegisterSnapshot {
+  /// Get the system status at the time of the snapshot.
+  pub const fn status(&self) -> Status {
+    Status { status: self.stat }
+  }
+
+  /// Get the raw value of the register at the given address.
+  ///
+  /// Returns `None` for addresses which do not correspond to a register.
+  pub fn raw(&self, addr: u8) -> Option<u32> {
+    Some(match addr {
+      Stat::ADDR => self.stat.bits().into(),
+      Ctrl1::ADDR => self.ctrl1.bits().into(),
+      Ctrl2::ADDR => self.ctrl2.bits().into(),
+      Ctrl3::ADDR => self.ctrl3.bits().into(),
+      Ctrl4::ADDR => self.ctrl4.bits().into(),
+      Ctrl5::ADDR => self.ctrl5.bits().into(),
+      Data24::ADDR => self.data,
+      SocSpi::ADDR => self.soc_spi.0.into(),
+      SgcSpi::ADDR => self.sgc_spi.0.into(),
+      ScocSpi::ADDR => self.scoc_spi.0.into(),
+      ScgcSpi::ADDR => self.scgc_spi.0.into(),
+      Hpf::ADDR => self.hpf.0.into(),
+      SocAdc::ADDR => self.soc_adc.0.into(),
+      SgcAdc::ADDR => self.sgc_adc.0.into(),
+      ScocAdc::ADDR => self.scoc_adc.0.into(),
+      ScgcAdc::ADDR => self.scgc_adc.0.into(),
+      _ => return None,
+    })
+  }
+}
+
+impl fmt::Debug for RegisterSnapshot {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let status = self.status();
+    let config = Config::from_regs(self.ctrl1, self.ctrl2, self.ctrl3, self.ctrl4, self.ctrl5);
+
+    f.debug_struct("RegisterSnapshot")
+      .field("state", &status.state())
+      .field("rate", &status.data_rate())
+      .field("in_reset", &self.stat.contains(Stat::INRESET))
+      .field("error", &self.stat.contains(Stat::ERROR))
+      .field("data_ready", &status.data_ready())
+      .field("modulator_busy", &status.modulator_busy())
+      .field("data_overrange", &status.data_overrange())
+      .field("analog_overrange", &status.analog_overrange())
+      .field("system_gain_overrange", &status.system_gain_overrange())
+      .field("data_read_error", &status.data_read_error())
+      .field("single_cycle", &self.ctrl1.contains(Ctrl1::SCYCLE))
+      .field("calibration", &self.ctrl5.calibration())
+      .field("config", &config)
+      .field("data", &format_args!("{:#010X}", self.data))
+      .field("soc_spi", &self.soc_spi.0)
+      .field("sgc_spi", &self.sgc_spi.0)
+      .field("scoc_spi", &self.scoc_spi.0)
+      .field("scgc_spi", &self.scgc_spi.0)
+      .field("hpf", &format_args!("{:#06X}", self.hpf.0))
+      .field("soc_adc", &self.soc_adc.0)
+      .field("sgc_adc", &self.sgc_adc.0)
+      .field("scoc_adc", &self.scoc_adc.0)
+      .field("scgc_adc", &self.scgc_adc.0)
+      .finish()
+  }
+}
@@ -0,0 +1,248 @@
+//! Windowed power spectrum of captured conversion results.
+//!
+//! Powers are single-sided and relative to a full-scale sine wave, i.e. a sine wave spanning the whole bipolar code
+//! range reads as 0 dBFS when summed over its main lobe.
+//!
+//! ```rust
+//! use max112x::{spectrum::{Spectrum, Window}, ConversionMode, ConversionRate};
+//!
+//! // A 100 Hz sine wave at -6 dBFS with a small second harmonic.
+//! let mut samples = [0; 1024];
+//! for (i, sample) in samples.iter_mut().enumerate() {
+//!   let phase = 2.0 * core::f64::consts::PI * 100.0 * i as f64 / 1000.0;
+//!   let x = 0.5 * phase.sin() + 0.001 * (2.0 * phase).sin();
+//!   *sample = (x * 8_388_607.0) as i32;
+//! }
+//!
+//! let (rate, mode) = (ConversionRate::Hz1000, ConversionMode::Continuous);
+//! let spectrum = Spectrum::new(&samples, rate, mode, Window::BlackmanHarris).unwrap();
+//! let analysis = spectrum.analyze().unwrap();
+//!
+//! assert!((analysis.fundamental_hz - 100.0).abs() < 1.0);
+//! assert!((analysis.fundamental_dbfs + 6.0).abs() < 0.1);
+//! assert!((analysis.thd_db + 54.0).abs() < 0.5);
+//! ```
+
+use core::f32::consts::PI;
+
+use crate::{filter_response::to_db, ConversionMode, ConversionRate};
+
+/// Number of harmonics, including the fundamental, taken into account for the total harmonic distortion.
+pub const HARMONICS: usize = 6;
+
+/// Window function applied before the transform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+  /// No window, only suitable for coherently sampled signals.
+  Rectangular,
+  /// Hann window.
+  Hann,
+  /// 4-term Blackman-Harris window with about 92 dB sidelobe attenuation.
+  BlackmanHarris,
+}
+
+impl Window {
+  /// Get the window coefficient at position `i` of `n`.
+  fn coefficient(self, i: usize, n: usize) -> f32 {
+    let x = 2.0 * PI * i as f32 / n as f32;
+
+    match self {
+      Self::Rectangular => 1.0,
+      Self::Hann => 0.5 - 0.5 * libm::cosf(x),
+      Self::BlackmanHarris => {
+        0.35875 - 0.48829 * libm::cosf(x) + 0.14128 * libm::cosf(2.0 * x) - 0.01168 * libm::cosf(3.0 * x)
+      },
+    }
+  }
+
+  /// Get the number of bins on each side of a tone which belong to its main lobe.
+  pub const fn lobe_bins(self) -> usize {
+    match self {
+      Self::Rectangular => 1,
+      Self::Hann => 2,
+      Self::BlackmanHarris => 4,
+    }
+  }
+}
+
+/// Single-sided power spectrum of `N` conversion results.
+///
+/// Only the first `N / 2 + 1` bins are used. Computing the spectrum needs two additional `[f32; N]` buffers on the
+/// stack.
+#[derive(Debug, Clone)]
+pub struct Spectrum<const N: usize> {
+  power: [f32; N],
+  bin_hz: f32,
+  window: Window,
+}
+
+impl<const N: usize> Spectrum<N> {
+  /// Compute the spectrum of conversion results taken at the nominal conversion rate.
+  ///
+  /// Returns `None` if `N` is not a power of two of at least 8.
+  pub fn new(samples: &[i32; N], rate: ConversionRate, mode: ConversionMode, window: Window) -> Option<Self> {
+    if N < 8 || !N.is_power_of_two() {
+      return None
+    }
+
+    let mean = samples.iter().map(|&v| i64::from(v)).sum::<i64>() as f32 / N as f32;
+
+    let mut re = [0.0f32; N];
+    let mut im = [0.0f32; N];
+    let mut window_power = 0.0;
+    for (i, (re, &value)) in re.iter_mut().zip(samples).enumerate() {
+      let w = window.coefficient(i, N);
+      window_power += w * w;
+      *re = (value as f32 - mean) * w;
+    }
+
+    fft(&mut re, &mut im);
+
+    // Normalize, so the main lobe of a sine wave sums up to its mean square, relative to a full-scale sine wave.
+    let full_scale = (1u32 << 23) as f32;
+    let scale = 2.0 / (N as f32 * window_power) / (full_scale * full_scale / 2.0);
+
+    let mut power = [0.0; N];
+    for (k, power) in power[..=N / 2].iter_mut().enumerate() {
+      let bin = (re[k] * re[k] + im[k] * im[k]) * scale;
+      *power = if k == 0 || k == N / 2 { bin / 2.0 } else { bin };
+    }
+
+    Some(Self { power, bin_hz: rate.hz(mode).to_f32() / N as f32, window })
+  }
+
+  /// Get the relative power of all bins from DC to the Nyquist frequency.
+  pub fn power(&self) -> &[f32] {
+    &self.power[..=N / 2]
+  }
+
+  /// Get the power of a bin in dBFS.
+  pub fn power_dbfs(&self, bin: usize) -> Option<f32> {
+    self.power().get(bin).map(|&p| to_db(p) / 2.0)
+  }
+
+  /// Get the frequency resolution in hertz.
+  pub const fn bin_hz(&self) -> f32 {
+    self.bin_hz
+  }
+
+  /// Get the center frequency of a bin in hertz.
+  pub fn frequency(&self, bin: usize) -> f32 {
+    bin as f32 * self.bin_hz
+  }
+
+  /// Iterate over the frequency axis in hertz and the power of each bin in dBFS.
+  pub fn iter(&self) -> impl Iterator<Item = (f32, f32)> + '_ {
+    self.power().iter().enumerate().map(|(bin, &p)| (self.frequency(bin), to_db(p) / 2.0))
+  }
+
+  /// Analyze the spectrum, taking the largest tone as the fundamental.
+  ///
+  /// Returns `None` if there is no tone outside of the DC bins.
+  pub fn analyze(&self) -> Option<Analysis> {
+    let power = self.power();
+    let lobe = self.window.lobe_bins();
+    let last = power.len() - 1;
+
+    let (fundamental, _) =
+      power.iter().enumerate().skip(lobe + 1).max_by(|(_, a), (_, b)| a.total_cmp(b)).filter(|(_, &p)| p > 0.0)?;
+
+    // Bins belonging to DC, the fundamental or a harmonic.
+    let mut used = [(0, lobe); HARMONICS + 1];
+    let range = |bin: usize| (bin.saturating_sub(lobe), (bin + lobe).min(last));
+    used[1] = range(fundamental);
+
+    let mut harmonic_power = 0.0;
+    for (h, used) in used.iter_mut().enumerate().skip(2) {
+      // Harmonics above the Nyquist frequency are aliased.
+      let bin = (fundamental * h) % (2 * last);
+      let bin = if bin > last { 2 * last - bin } else { bin };
+      *used = range(bin);
+
+      if bin > lobe && bin.abs_diff(fundamental) > 2 * lobe {
+        harmonic_power += power[used.0..=used.1].iter().sum::<f32>();
+      }
+    }
+
+    let is_used = |bin: usize, to: usize| used[..to].iter().any(|&(start, end)| (start..=end).contains(&bin));
+
+    let fundamental_power = power[used[1].0..=used[1].1].iter().sum::<f32>();
+
+    let (mut noise_power, mut noise_bins) = (0.0, 0);
+    let mut spur = 0.0f32;
+    for (bin, &p) in power.iter().enumerate() {
+      if !is_used(bin, 2) {
+        spur = spur.max(p);
+      }
+
+      if !is_used(bin, used.len()) {
+        noise_power += p;
+        noise_bins += 1;
+      }
+    }
+
+    // Extrapolate the noise to the bins occupied by tones.
+    let noise_floor = noise_power / noise_bins.max(1) as f32;
+    let total_noise = noise_floor * (power.len() - (lobe + 1)) as f32;
+
+    Some(Analysis {
+      fundamental_hz: self.frequency(fundamental),
+      fundamental_dbfs: to_db(fundamental_power) / 2.0,
+      noise_floor_dbfs: to_db(noise_floor) / 2.0,
+      snr_db: to_db(fundamental_power / total_noise) / 2.0,
+      thd_db: to_db(harmonic_power / fundamental_power) / 2.0,
+      sfdr_db: to_db(power[fundamental] / spur) / 2.0,
+    })
+  }
+}
+
+/// Figures of merit of a spectrum containing a single tone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Analysis {
+  /// Frequency of the fundamental in hertz, at bin resolution.
+  pub fundamental_hz: f32,
+  /// Power of the fundamental in dBFS.
+  pub fundamental_dbfs: f32,
+  /// Average noise power per bin in dBFS.
+  pub noise_floor_dbfs: f32,
+  /// Signal-to-noise ratio in decibels, excluding harmonics.
+  pub snr_db: f32,
+  /// Total harmonic distortion in decibels relative to the fundamental.
+  pub thd_db: f32,
+  /// Spurious-free dynamic range in decibels, relative to the largest other bin.
+  pub sfdr_db: f32,
+}
+
+/// In-place radix-2 FFT. The length must be a power of two.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+  let n = re.len();
+  let bits = n.trailing_zeros();
+
+  for i in 0..n {
+    let j = i.reverse_bits() >> (usize::BITS - bits);
+    if i < j {
+      re.swap(i, j);
+      im.swap(i, j);
+    }
+  }
+
+  let mut len = 2;
+  while len <= n {
+    let half = len / 2;
+    for k in 0..half {
+      let angle = -2.0 * PI * k as f32 / len as f32;
+      let (w_im, w_re) = (libm::sinf(angle), libm::cosf(angle));
+
+      for start in (0..n).step_by(len) {
+        let (a, b) = (start + k, start + k + half);
+        let t_re = re[b] * w_re - im[b] * w_im;
+        let t_im = re[b] * w_im + im[b] * w_re;
+        re[b] = re[a] - t_re;
+        im[b] = im[a] - t_im;
+        re[a] += t_re;
+        im[a] += t_im;
+      }
+    }
+    len *= 2;
+  }
+}
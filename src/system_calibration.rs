@@ -0,0 +1,161 @@
+use core::marker::PhantomData;
+
+use embedded_hal::spi::SpiDevice;
+
+use crate::{register::Ctrl5, Calibration, CalibrationData, ConversionRate, Error, Max11214, Standby};
+
+/// Marker type for a [`SystemCalibrator`] waiting for a zero input to be applied.
+#[derive(Debug)]
+pub enum ZeroInput {}
+
+/// Marker type for a [`SystemCalibrator`] waiting for a full-scale input to be applied.
+#[derive(Debug)]
+pub enum FullScaleInput {}
+
+/// Marker type for a [`SystemCalibrator`] whose coefficients have not been validated yet.
+#[derive(Debug)]
+pub enum Unvalidated {}
+
+/// Marker type for a [`SystemCalibrator`] whose coefficients are valid and can be enabled.
+#[derive(Debug)]
+pub enum Validated {}
+
+/// Guided two-point system calibration.
+///
+/// Created by [`Max11214::system_calibrator`]. The calibration consists of the following steps:
+///
+/// 1. Apply a zero input, then call [`calibrate_offset`](SystemCalibrator::calibrate_offset).
+/// 2. Apply a full-scale input, then call [`calibrate_gain`](SystemCalibrator::calibrate_gain).
+/// 3. Inspect the [`coefficients`](SystemCalibrator::coefficients), then call
+///    [`validate`](SystemCalibrator::validate).
+/// 4. Call [`commit`](SystemCalibrator::commit) to enable the system calibration coefficients.
+///
+/// The system calibration coefficients are disabled when the calibrator is created, so dropping it before committing
+/// leaves them disabled.
+///
+/// ```rust
+/// # use embedded_hal::spi::SpiDevice;
+/// # use max112x::{Error, Max11214, Standby};
+/// # fn example<SPI: SpiDevice>(adc: &mut Max11214<SPI, Standby>) -> Result<(), Error<SPI::Error>> {
+/// use max112x::ConversionRate;
+///
+/// let calibrator = adc.system_calibrator(ConversionRate::Hz62_5)?;
+/// // Apply a zero input.
+/// let calibrator = calibrator.calibrate_offset()?;
+/// // Apply a full-scale input.
+/// let calibrator = calibrator.calibrate_gain()?;
+///
+/// let data = calibrator.validate()?.commit()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SystemCalibrator<'a, SPI, STEP, const N: usize = 0> {
+  adc: &'a mut Max11214<SPI, Standby, N>,
+  rate: ConversionRate,
+  overrange: bool,
+  data: CalibrationData,
+  step: PhantomData<STEP>,
+}
+
+impl<'a, SPI, STEP, const N: usize> SystemCalibrator<'a, SPI, STEP, N> {
+  fn into_step<NEXT>(self) -> SystemCalibrator<'a, SPI, NEXT, N> {
+    SystemCalibrator { adc: self.adc, rate: self.rate, overrange: self.overrange, data: self.data, step: PhantomData }
+  }
+}
+
+impl<'a, SPI, E, const N: usize> SystemCalibrator<'a, SPI, ZeroInput, N>
+where
+  SPI: SpiDevice<u8, Error = E>,
+{
+  /// Run the system offset calibration with a zero input applied.
+  ///
+  /// Returns [`Error::CalibrationOverrange`] if the input was overranged.
+  pub fn calibrate_offset(self) -> Result<SystemCalibrator<'a, SPI, FullScaleInput, N>, Error<E>> {
+    self.adc.calibrate(Calibration::SystemOffsetCalibration, self.rate)?;
+
+    if self.adc.status()?.analog_overrange() {
+      return Err(Error::CalibrationOverrange)
+    }
+
+    Ok(self.into_step())
+  }
+}
+
+impl<'a, SPI, E, const N: usize> SystemCalibrator<'a, SPI, FullScaleInput, N>
+where
+  SPI: SpiDevice<u8, Error = E>,
+{
+  /// Run the system gain calibration with a full-scale input applied.
+  pub fn calibrate_gain(mut self) -> Result<SystemCalibrator<'a, SPI, Unvalidated, N>, Error<E>> {
+    self.adc.calibrate(Calibration::SystemFullScaleCalibration, self.rate)?;
+
+    let status = self.adc.status()?;
+    self.overrange |= status.system_gain_overrange() || status.analog_overrange();
+    self.data = self.adc.read_calibration()?;
+
+    Ok(self.into_step())
+  }
+}
+
+impl<'a, SPI, E, const N: usize> SystemCalibrator<'a, SPI, Unvalidated, N>
+where
+  SPI: SpiDevice<u8, Error = E>,
+{
+  /// Get the calibration coefficients.
+  pub const fn coefficients(&self) -> &CalibrationData {
+    &self.data
+  }
+
+  /// Check that the input was not overranged during the gain calibration and that the gain coefficient is valid.
+  ///
+  /// Returns [`Error::CalibrationOverrange`] if the input was overranged and [`Error::CalibrationZeroGain`] if the
+  /// gain coefficient is zero.
+  pub fn validate(self) -> Result<SystemCalibrator<'a, SPI, Validated, N>, Error<E>> {
+    if self.overrange {
+      return Err(Error::CalibrationOverrange)
+    }
+
+    if self.data.system_gain == 0 {
+      return Err(Error::CalibrationZeroGain)
+    }
+
+    Ok(self.into_step())
+  }
+}
+
+impl<SPI, E, const N: usize> SystemCalibrator<'_, SPI, Validated, N>
+where
+  SPI: SpiDevice<u8, Error = E>,
+{
+  /// Get the calibration coefficients.
+  pub const fn coefficients(&self) -> &CalibrationData {
+    &self.data
+  }
+
+  /// Enable the system calibration coefficients.
+  ///
+  /// Returns the calibration coefficients.
+  pub fn commit(self) -> Result<CalibrationData, Error<E>> {
+    self.adc.modify_reg_u8(|ctrl5: Ctrl5| ctrl5.difference(Ctrl5::NOSYSO).difference(Ctrl5::NOSYSG))?;
+
+    Ok(self.data)
+  }
+}
+
+impl<SPI, E, const N: usize> Max11214<SPI, Standby, N>
+where
+  SPI: SpiDevice<u8, Error = E>,
+{
+  /// Start a guided two-point system calibration at the given conversion rate.
+  ///
+  /// The system calibration coefficients are disabled until the calibration is committed.
+  pub fn system_calibrator(
+    &mut self,
+    rate: ConversionRate,
+  ) -> Result<SystemCalibrator<'_, SPI, ZeroInput, N>, Error<E>> {
+    self.modify_reg_u8(|ctrl5: Ctrl5| ctrl5.union(Ctrl5::NOSYSO).union(Ctrl5::NOSYSG))?;
+
+    Ok(SystemCalibrator { adc: self, rate, overrange: false, data: CalibrationData::default(), step: PhantomData })
+  }
+}
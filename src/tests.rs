@@ -0,0 +1,791 @@
+use embedded_hal::spi::SpiDevice;
+use embedded_hal_mock::eh1::spi::{Mock as SpiMock, Transaction as SpiTransaction};
+
+use crate::{
+  capture::{Capture, Trigger},
+  register::Stat,
+  AutoRange, Calibration, CalibrationTable, Clock, Conversion, ConversionRate, DataReady, Error, Max11214, Nominal,
+  Pga, RecalibrationSchedule, SelfCalibrate, Standby, State,
+};
+
+type Transactions = Vec<SpiTransaction<u8>>;
+
+fn read(write: &[u8], read: &[u8]) -> Transactions {
+  vec![
+    SpiTransaction::transaction_start(),
+    SpiTransaction::transfer_in_place(write.to_vec(), read.to_vec()),
+    SpiTransaction::transaction_end(),
+  ]
+}
+
+fn write(write: &[u8]) -> Transactions {
+  vec![
+    SpiTransaction::transaction_start(),
+    SpiTransaction::write_vec(write.to_vec()),
+    SpiTransaction::transaction_end(),
+  ]
+}
+
+/// Start a conversion from power-down mode with the given `CTRL3` value.
+fn start(rate: ConversionRate, ctrl3: u8) -> Transactions {
+  [
+    read(&[0b11000011, 0], &[0b11000011, 0b00100000]),
+    write(&[0b11000010, 0b00000000]),
+    read(&[0b11000111, 0], &[0b11000111, ctrl3]),
+    write(&[0b10000000 | rate as u8]),
+  ]
+  .concat()
+}
+
+/// Switch from conversion to standby mode.
+fn standby() -> Transactions {
+  [read(&[0b11000011, 0], &[0b11000011, 0b00000000]), write(&[0b11000010, 0b00100000]), write(&[0b10010000])].concat()
+}
+
+/// Read the `CTRL1` to `CTRL5` registers.
+fn ctrl(values: [u8; 5]) -> Transactions {
+  values
+    .into_iter()
+    .zip(1u8..)
+    .flat_map(|(value, addr)| read(&[0b11000001 | addr << 1, 0], &[0b11000001 | addr << 1, value]))
+    .collect()
+}
+
+/// Read a conversion result with the given status, optionally colliding with the next result.
+fn sample(stat: Stat, value: u32, collided: bool) -> Transactions {
+  let [stat1, stat0] = stat.bits().to_be_bytes();
+  let [_, data2, data1, data0] = value.to_be_bytes();
+  let [rderr1, rderr0] = if collided { Stat::RDERR } else { Stat::empty() }.bits().to_be_bytes();
+
+  [
+    read(&[0b11000001, 0, 0], &[0b11000001, stat1, stat0]),
+    read(&[0b11001101, 0, 0, 0], &[0b11001101, data2, data1, data0]),
+    read(&[0b11000001, 0, 0], &[0b11000001, rderr1, rderr0]),
+  ]
+  .concat()
+}
+
+/// Read a fresh conversion result.
+fn fresh(value: u32) -> Transactions {
+  sample(Stat::RDY, value, false)
+}
+
+/// A conversion result is always ready.
+struct Immediate;
+
+impl DataReady for Immediate {
+  fn wait<SPI, const N: usize>(&mut self, _: &mut Max11214<SPI, Conversion, N>) -> Result<(), Error<SPI::Error>>
+  where
+    SPI: SpiDevice<u8>,
+  {
+    Ok(())
+  }
+}
+
+fn standby_adc(transactions: &[Transactions]) -> Max11214<SpiMock<u8>, Standby> {
+  Max11214::new(SpiMock::new(&transactions.concat()))
+}
+
+fn converting(transactions: &[Transactions]) -> Max11214<SpiMock<u8>, Conversion> {
+  let spi = SpiMock::new(&transactions.concat());
+  Max11214::new(spi).start_conversion(ConversionRate::Hz125, true).unwrap()
+}
+
+fn done<const N: usize>(adc: Max11214<SpiMock<u8>, Conversion, N>) {
+  adc.into_standby().unwrap().release().done();
+}
+
+#[test]
+fn acquire_skips_collided_and_stale_results() {
+  let mut adc = converting(&[
+    start(ConversionRate::Hz125, 0),
+    fresh(1),
+    sample(Stat::RDY, 2, true),
+    sample(Stat::empty(), 2, false),
+    fresh(3),
+    standby(),
+  ]);
+
+  let mut buf = [0; 2];
+  let missed = adc.acquire(&mut buf, &mut Immediate).unwrap();
+
+  assert_eq!(buf, [1, 3]);
+  assert_eq!(missed, 1);
+  done(adc);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn acquire_into_queue_counts_results_not_fitting() {
+  let mut adc = converting(&[start(ConversionRate::Hz125, 0), fresh(1), fresh(2), fresh(3), standby()]);
+
+  let mut queue = heapless::spsc::Queue::<i32, 3>::new();
+  let (mut producer, mut consumer) = queue.split();
+
+  let missed = adc.acquire_into_queue(&mut producer, 3, &mut Immediate).unwrap();
+
+  assert_eq!(missed, 1);
+  assert_eq!(consumer.dequeue(), Some(1));
+  assert_eq!(consumer.dequeue(), Some(2));
+  assert_eq!(consumer.dequeue(), None);
+  done(adc);
+}
+
+/// A clock returning the given times in nanoseconds.
+struct Times(std::vec::IntoIter<u64>);
+
+impl Clock for Times {
+  fn now_ns(&mut self) -> u64 {
+    self.0.next().unwrap()
+  }
+}
+
+#[test]
+fn timestamped_samples_detect_gaps() {
+  let mut adc =
+    converting(&[start(ConversionRate::Hz125, 0), fresh(1), sample(Stat::RDY, 2, true), fresh(3), fresh(4), standby()]);
+
+  // The conversion period is 8 ms.
+  let clock = Times(vec![0, 16_000_000, 40_000_000].into_iter());
+  let mut ready = Immediate;
+  let mut samples = adc.timestamped_samples(&mut ready, clock);
+
+  let first = samples.next().unwrap().unwrap();
+  assert_eq!((first.index, first.missed, first.timestamp_ns), (0, 0, 0));
+  assert!(!first.is_discontinuity());
+
+  // The second result collided while being read.
+  let third = samples.next().unwrap().unwrap();
+  assert_eq!((third.index, third.missed, third.sample.value()), (2, 1, 3));
+
+  // Two results were overwritten before being read, detected using the clock.
+  let sixth = samples.next().unwrap().unwrap();
+  assert_eq!((sixth.index, sixth.missed, sixth.sample.value()), (5, 2, 4));
+  assert!(sixth.is_discontinuity());
+
+  drop(samples);
+  done(adc);
+}
+
+#[test]
+fn recalibrating_marks_gap_as_discontinuity() {
+  let mut adc = converting(&[
+    start(ConversionRate::Hz125, 0b00000010),
+    fresh(1),
+    fresh(2),
+    // Pause the conversion for a self-calibration.
+    standby(),
+    read(&[0b11001011, 0], &[0b11001011, 0]),
+    vec![
+      SpiTransaction::transaction_start(),
+      SpiTransaction::write_vec(vec![0b10100000 | ConversionRate::Hz125 as u8]),
+      SpiTransaction::delay(200_000_000),
+      SpiTransaction::transaction_end(),
+    ],
+    // Restart the conversion, discarding three unsettled results with the SINC filter.
+    start(ConversionRate::Hz125, 0b00000010),
+    fresh(0),
+    fresh(0),
+    fresh(0),
+    fresh(3),
+    standby(),
+  ]);
+
+  let schedule = RecalibrationSchedule::new(Calibration::SelfCalibration).interval_ns(8_000_000);
+  let mut ready = Immediate;
+  let mut samples = adc.timestamped_samples(&mut ready, Nominal).recalibrating(schedule, ());
+
+  // The recalibration is due after the second result.
+  let indices = [samples.next().unwrap().unwrap().index, samples.next().unwrap().unwrap().index];
+  assert_eq!(indices, [0, 1]);
+
+  // The 200 ms calibration and 32 ms settling time span 29 conversion periods of 8 ms.
+  let third = samples.next().unwrap().unwrap();
+  assert_eq!((third.index, third.missed, third.sample.value()), (31, 29, 3));
+  assert!(third.is_discontinuity());
+  done(adc);
+}
+
+#[test]
+fn capture_keeps_pre_trigger_results() {
+  let mut adc = converting(&[
+    start(ConversionRate::Hz125, 0),
+    fresh(0),
+    fresh(10),
+    fresh(20),
+    fresh(150),
+    fresh(160),
+    fresh(170),
+    standby(),
+  ]);
+
+  let mut capture = Capture::<5>::new(Trigger::Rising(100), 2);
+  let record = adc.capture(&mut Immediate, &mut capture).unwrap();
+
+  assert_eq!(record.trigger_index(), 2);
+  assert_eq!(record.values().collect::<Vec<_>>(), [10, 20, 150, 160, 170]);
+  done(adc);
+}
+
+#[test]
+fn reconfigure_discards_unsettled_results() {
+  let mut adc = converting(&[
+    start(ConversionRate::Hz125, 0b00000010),
+    // Read the configuration.
+    ctrl([0b00000000, 0, 0b00000010, 0, 0]),
+    // Pause the conversion.
+    read(&[0b11000011, 0], &[0b11000011, 0b00000000]),
+    write(&[0b11000010, 0b00100000]),
+    write(&[0b10010000]),
+    // Write the configuration.
+    read(&[0b11000011, 0], &[0b11000011, 0b00100000]),
+    read(&[0b11000101, 0], &[0b11000101, 0]),
+    write(&[0b11000100, 0b00001001]),
+    read(&[0b11000111, 0], &[0b11000111, 0b00000010]),
+    read(&[0b11001001, 0], &[0b11001001, 0]),
+    read(&[0b11001011, 0], &[0b11001011, 0]),
+    // Restart the conversion.
+    start(ConversionRate::Hz125, 0b00000010),
+    fresh(1),
+    fresh(2),
+    fresh(3),
+    fresh(4),
+    standby(),
+  ]);
+
+  adc.reconfigure(|config| config.pga = Some(Pga::X2)).unwrap();
+
+  // The first three results after changing the configuration are not settled with the SINC filter.
+  let sample = adc.samples(&mut Immediate).next().unwrap().unwrap();
+  assert_eq!(sample.value(), 4);
+  done(adc);
+}
+
+#[test]
+fn reconfigure_without_changes_keeps_converting() {
+  let mut adc = converting(&[start(ConversionRate::Hz125, 0), ctrl([0, 0b00001001, 0, 0, 0]), fresh(1), standby()]);
+
+  adc.reconfigure(|config| config.pga = Some(Pga::X2)).unwrap();
+
+  let sample = adc.samples(&mut Immediate).next().unwrap().unwrap();
+  assert_eq!(sample.value(), 1);
+  done(adc);
+}
+
+#[test]
+fn write_verification_retries_and_invalidates_cache() {
+  let mut adc = standby_adc(&[
+    // Fill the cache.
+    ctrl([0b00100000, 0, 0, 0, 0]),
+    // Write and retry once, both reading back the old value.
+    write(&[0b11000100, 0b00001001]),
+    read(&[0b11000101, 0], &[0b11000101, 0]),
+    write(&[0b11000100, 0b00001001]),
+    read(&[0b11000101, 0], &[0b11000101, 0]),
+    // Re-read the invalidated cache, then write successfully.
+    read(&[0b11000101, 0], &[0b11000101, 0]),
+    write(&[0b11000100, 0b00001001]),
+    read(&[0b11000101, 0], &[0b11000101, 0b00001001]),
+  ]);
+
+  adc.enable_cache().unwrap();
+  adc.enable_write_verification(1);
+
+  let result = adc.set_pga(Some(Pga::X2));
+  assert!(matches!(result, Err(Error::VerifyFailed { addr: 0x2, wrote: 0b00001001, read: 0 })));
+
+  adc.set_pga(Some(Pga::X2)).unwrap();
+  adc.release().done();
+}
+
+#[test]
+fn dump_and_restore_registers() {
+  let mut adc = standby_adc(&[
+    // Dump registers.
+    read(&[0b11000001, 0, 0], &[0b11000001, 0b00001000, 0b01010000]),
+    ctrl([0b00100000, 0b00001111, 0b00000010, 0b00000000, 0b00001100]),
+    read(&[0b11001101, 0, 0, 0], &[0b11001101, 0x00, 0x12, 0x34]),
+    read(&[0b11001111, 0, 0, 0], &[0b11001111, 0x00, 0x00, 0x10]),
+    read(&[0b11010001, 0, 0, 0], &[0b11010001, 0xC0, 0x00, 0x00]),
+    read(&[0b11010011, 0, 0, 0], &[0b11010011, 0x00, 0x00, 0x20]),
+    read(&[0b11010101, 0, 0, 0], &[0b11010101, 0xB0, 0x00, 0x00]),
+    read(&[0b11010111, 0, 0], &[0b11010111, 0x00, 0x00]),
+    read(&[0b11101011, 0, 0, 0], &[0b11101011, 0x00, 0x00, 0x10]),
+    read(&[0b11101101, 0, 0, 0], &[0b11101101, 0xC0, 0x00, 0x00]),
+    read(&[0b11101111, 0, 0, 0], &[0b11101111, 0x00, 0x00, 0x20]),
+    read(&[0b11110001, 0, 0, 0], &[0b11110001, 0xB0, 0x00, 0x00]),
+    // Restore registers, keeping the current power-down bits.
+    write(&[0b11001110, 0x00, 0x00, 0x10]),
+    write(&[0b11010000, 0xC0, 0x00, 0x00]),
+    write(&[0b11010010, 0x00, 0x00, 0x20]),
+    write(&[0b11010100, 0xB0, 0x00, 0x00]),
+    write(&[0b11010110, 0x00, 0x00]),
+    write(&[0b11001010, 0b00001100]),
+    write(&[0b11001000, 0b00000000]),
+    write(&[0b11000110, 0b00000010]),
+    write(&[0b11000100, 0b00001111]),
+    read(&[0b11000011, 0], &[0b11000011, 0b00100001]),
+    write(&[0b11000010, 0b00100000]),
+  ]);
+
+  let snapshot = adc.dump_registers().unwrap();
+  assert_eq!(snapshot.status().state(), State::Standby);
+
+  let debug = format!("{snapshot:?}");
+  assert!(debug.contains("state: Standby"));
+  assert!(debug.contains("rate: Hz31_25"));
+  assert!(debug.contains("pga: Some(X128)"));
+
+  adc.restore_registers(&snapshot).unwrap();
+  adc.release().done();
+}
+
+#[test]
+fn calibration_table_is_loaded_when_starting_conversion() {
+  let rate = ConversionRate::Hz1000;
+  let self_calibrate = |rate| {
+    [
+      read(&[0b11001011, 0], &[0b11001011, 0]),
+      vec![
+        SpiTransaction::transaction_start(),
+        SpiTransaction::write_vec(vec![0b10100000 | rate as u8]),
+        SpiTransaction::delay(200_000_000),
+        SpiTransaction::transaction_end(),
+      ],
+    ]
+    .concat()
+  };
+  let start = [
+    read(&[0b11000011, 0], &[0b11000011, 0b00100000]),
+    write(&[0b11000010, 0b00000000]),
+    read(&[0b11000111, 0], &[0b11000111, 0]),
+    read(&[0b11000101, 0], &[0b11000101, 0b00001001]),
+  ]
+  .concat();
+  let load = [
+    write(&[0b11001110, 0, 0, 0]),
+    write(&[0b11010000, 0x40, 0, 0]),
+    write(&[0b11010010, 0, 0, 0x12]),
+    write(&[0b11010100, 0x3f, 0xff, 0xf0]),
+  ]
+  .concat();
+  let convert = write(&[0b10000000 | rate as u8]);
+
+  let adc = standby_adc(&[
+    // Self-calibrate with PGA X2.
+    read(&[0b11000101, 0], &[0b11000101, 0]),
+    write(&[0b11000100, 0b00001001]),
+    self_calibrate(rate),
+    read(&[0b11101011, 0, 0, 0], &[0b11101011, 0, 0, 0]),
+    read(&[0b11101101, 0, 0, 0], &[0b11101101, 0x40, 0, 0]),
+    read(&[0b11101111, 0, 0, 0], &[0b11101111, 0, 0, 0x12]),
+    read(&[0b11110001, 0, 0, 0], &[0b11110001, 0x3f, 0xff, 0xf0]),
+    // The coefficients are loaded when starting the first conversion.
+    start.clone(),
+    load.clone(),
+    convert.clone(),
+    standby(),
+    // They are still loaded when starting the second conversion.
+    start.clone(),
+    convert.clone(),
+    standby(),
+    // A self-calibration overwrites them, so they are loaded again.
+    self_calibrate(ConversionRate::Hz0_95),
+    start,
+    load,
+    convert,
+    standby(),
+  ]);
+
+  let mut adc = adc.with_calibration_table(CalibrationTable::<4>::new());
+  assert_eq!(adc.calibrate_table(&[(Some(Pga::X2), rate)]).unwrap(), 0);
+  assert_eq!(adc.calibration_table().iter().count(), 1);
+
+  let adc = adc.start_conversion(rate, true).unwrap().into_standby().unwrap();
+  let mut adc = adc.start_conversion(rate, true).unwrap().into_standby().unwrap();
+
+  adc.self_calibrate(Calibration::SelfCalibration).unwrap();
+  let adc = adc.start_conversion(rate, true).unwrap();
+  done(adc);
+}
+
+/// Run a system calibration step at 62.5 Hz with the given `CTRL5` value, reading the given status afterwards.
+fn system_calibrate(ctrl5: u8, cal: u8, stat: Stat) -> Transactions {
+  let [stat1, stat0] = stat.bits().to_be_bytes();
+
+  [
+    read(&[0b11001011, 0], &[0b11001011, ctrl5]),
+    write(&[0b11001010, ctrl5 & 0b00111111 | cal]),
+    vec![
+      SpiTransaction::transaction_start(),
+      SpiTransaction::write_vec(vec![0b10100000 | ConversionRate::Hz62_5 as u8]),
+      SpiTransaction::delay(100_000_000),
+      SpiTransaction::transaction_end(),
+    ],
+    read(&[0b11000001, 0, 0], &[0b11000001, stat1, stat0]),
+  ]
+  .concat()
+}
+
+/// Read the system and self-calibration coefficients.
+fn coefficients(system_offset: u8, system_gain: u8) -> Transactions {
+  [
+    read(&[0b11101011, 0, 0, 0], &[0b11101011, 0, 0, system_offset]),
+    read(&[0b11101101, 0, 0, 0], &[0b11101101, system_gain, 0, 0]),
+    read(&[0b11101111, 0, 0, 0], &[0b11101111, 0, 0, 0]),
+    read(&[0b11110001, 0, 0, 0], &[0b11110001, 0x40, 0, 0]),
+  ]
+  .concat()
+}
+
+/// Disable the system calibration coefficients when creating the calibrator.
+fn disable_system_calibration() -> Transactions {
+  [read(&[0b11001011, 0], &[0b11001011, 0]), write(&[0b11001010, 0b00001100])].concat()
+}
+
+#[test]
+fn system_calibration_enables_coefficients_on_commit() {
+  let mut adc = standby_adc(&[
+    disable_system_calibration(),
+    system_calibrate(0b00001100, 0b01000000, Stat::empty()),
+    system_calibrate(0b01001100, 0b10000000, Stat::empty()),
+    coefficients(0x20, 0xC0),
+    read(&[0b11001011, 0], &[0b11001011, 0b10001100]),
+    write(&[0b11001010, 0b10000000]),
+  ]);
+
+  let calibrator = adc.system_calibrator(ConversionRate::Hz62_5).unwrap();
+  let calibrator = calibrator.calibrate_offset().unwrap().calibrate_gain().unwrap();
+  assert_eq!(calibrator.coefficients().system_gain, 0xC00000);
+
+  let data = calibrator.validate().unwrap().commit().unwrap();
+  assert_eq!(data.system_offset, 0x20);
+  adc.release().done();
+}
+
+#[test]
+fn system_calibration_fails_on_offset_overrange() {
+  let mut adc = standby_adc(&[disable_system_calibration(), system_calibrate(0b00001100, 0b01000000, Stat::AOR)]);
+
+  let res = adc.system_calibrator(ConversionRate::Hz62_5).unwrap().calibrate_offset();
+  assert!(matches!(res, Err(Error::CalibrationOverrange)));
+  adc.release().done();
+}
+
+#[test]
+fn system_calibration_rejects_zero_gain() {
+  let mut adc = standby_adc(&[
+    disable_system_calibration(),
+    system_calibrate(0b00001100, 0b01000000, Stat::empty()),
+    system_calibrate(0b01001100, 0b10000000, Stat::empty()),
+    coefficients(0x20, 0),
+  ]);
+
+  let calibrator = adc.system_calibrator(ConversionRate::Hz62_5).unwrap();
+  let calibrator = calibrator.calibrate_offset().unwrap().calibrate_gain().unwrap();
+  assert!(matches!(calibrator.validate(), Err(Error::CalibrationZeroGain)));
+  adc.release().done();
+}
+
+/// Change the `CTRL2` register while converting with the SINC filter, discarding three unsettled results.
+fn change_ctrl2(old: u8, new: u8) -> Transactions {
+  [
+    ctrl([0b00000000, old, 0b00000010, 0, 0]),
+    standby(),
+    read(&[0b11000011, 0], &[0b11000011, 0b00100000]),
+    read(&[0b11000101, 0], &[0b11000101, old]),
+    write(&[0b11000100, new]),
+    read(&[0b11000111, 0], &[0b11000111, 0b00000010]),
+    read(&[0b11001001, 0], &[0b11001001, 0]),
+    read(&[0b11001011, 0], &[0b11001011, 0]),
+    start(ConversionRate::Hz125, 0b00000010),
+    fresh(0),
+    fresh(0),
+    fresh(0),
+  ]
+  .concat()
+}
+
+#[test]
+fn autorange_calibrates_on_first_use() {
+  let mut adc = converting(&[
+    start(ConversionRate::Hz125, 0b00000010),
+    read(&[0b11000101, 0], &[0b11000101, 0b00001000]),
+    // Pause the conversion for a self-calibration.
+    standby(),
+    read(&[0b11001011, 0], &[0b11001011, 0]),
+    vec![
+      SpiTransaction::transaction_start(),
+      SpiTransaction::write_vec(vec![0b10100000 | ConversionRate::Hz125 as u8]),
+      SpiTransaction::delay(200_000_000),
+      SpiTransaction::transaction_end(),
+    ],
+    start(ConversionRate::Hz125, 0b00000010),
+    fresh(0),
+    fresh(0),
+    fresh(0),
+    fresh(0x500000),
+    standby(),
+  ]);
+
+  let mut auto = AutoRange::new().calibration(SelfCalibrate);
+  let sample = adc.read_autoranged(&mut Immediate, &mut auto).unwrap();
+
+  assert_eq!((sample.pga, sample.sample.value()), (Pga::X1, 0x500000));
+  done(adc);
+}
+
+#[test]
+fn autorange_without_calibration_keeps_converting_on_first_use() {
+  let mut adc = converting(&[
+    start(ConversionRate::Hz125, 0b00000010),
+    read(&[0b11000101, 0], &[0b11000101, 0b00001001]),
+    fresh(0x500000),
+    standby(),
+  ]);
+
+  let mut auto = AutoRange::new();
+  let sample = adc.read_autoranged(&mut Immediate, &mut auto).unwrap();
+
+  assert_eq!((sample.pga, sample.sample.value()), (Pga::X2, 0x500000));
+  done(adc);
+}
+
+#[test]
+fn autorange_steps_gain_with_hysteresis() {
+  let mut adc = converting(&[
+    start(ConversionRate::Hz125, 0b00000010),
+    read(&[0b11000101, 0], &[0b11000101, 0b00001000]),
+    fresh(0x100000),
+    // Raise the gain after two results below the lower threshold.
+    fresh(0x100000),
+    change_ctrl2(0b00001000, 0b00001001),
+    fresh(0x100000),
+    // A result between the thresholds resets the hold count.
+    fresh(0x500000),
+    fresh(0x100000),
+    // Lower the gain after an analog overrange.
+    sample(Stat::RDY | Stat::AOR, 0x100000, false),
+    change_ctrl2(0b00001001, 0b00001000),
+    // Lower the gain after a clipped result, unless it is already the lowest allowed gain.
+    sample(Stat::RDY | Stat::DOR, 0x7FFFFF, false),
+    standby(),
+  ]);
+
+  let mut auto = AutoRange::new().gains(Pga::X1, Pga::X4).hold(2);
+  let pgas = (0..7).map(|_| adc.read_autoranged(&mut Immediate, &mut auto).unwrap().pga).collect::<Vec<_>>();
+
+  assert_eq!(pgas, [Pga::X1, Pga::X1, Pga::X2, Pga::X2, Pga::X2, Pga::X2, Pga::X1]);
+  assert_eq!(auto.current(), Some(Pga::X1));
+  done(adc);
+}
+
+#[test]
+fn measure_noise_excludes_invalid_results() {
+  let mut adc = converting(&[
+    start(ConversionRate::Hz125, 0),
+    read(&[0b11000101, 0], &[0b11000101, 0]),
+    fresh(10),
+    sample(Stat::RDY, 100, true),
+    sample(Stat::empty(), 100, false),
+    sample(Stat::RDY | Stat::DOR, 0x7FFFFF, false),
+    fresh(14),
+    fresh(12),
+    read(&[0b11000101, 0], &[0b11000101, 0]),
+    fresh(10),
+    standby(),
+  ]);
+
+  // The clipped result is read but not used.
+  let report = adc.measure_noise(&mut Immediate, 4, 2_500_000).unwrap().unwrap();
+  assert_eq!((report.count, report.mean, report.peak_to_peak), (3, 12.0, 4));
+
+  // A single result is not enough to determine the noise.
+  assert!(adc.measure_noise(&mut Immediate, 1, 2_500_000).unwrap().is_none());
+  done(adc);
+}
+
+#[test]
+fn cic_does_not_overflow_at_maximum_decimation() {
+  use crate::dsp::{Cic, Stage};
+
+  for (order, value) in [(2, 0xFFFFFF), (4, 0xFFFFFF), (4, i32::MAX), (4, i32::MIN)] {
+    let mut cic = Cic::new(order, u32::MAX);
+    let decimation = cic.decimation();
+
+    // The output settles after `order` output periods.
+    let output = (0..decimation * (u32::from(order) + 1)).filter_map(|_| cic.process(value)).last();
+    assert_eq!(output, Some(value), "order {order}");
+  }
+}
+
+#[test]
+fn filtered_drops_stale_and_collided_results() {
+  use crate::dsp::{Boxcar, SampleIteratorExt};
+
+  let mut adc = converting(&[
+    start(ConversionRate::Hz125, 0),
+    fresh(2),
+    sample(Stat::RDY, 100, true),
+    sample(Stat::empty(), 100, false),
+    fresh(4),
+    fresh(8),
+    standby(),
+  ]);
+
+  let mut ready = Immediate;
+  let values = adc.samples(&mut ready).filtered(Boxcar::<2>::new()).take(2).map(|sample| sample.unwrap().value());
+  assert_eq!(values.collect::<Vec<_>>(), [3, 6]);
+  done(adc);
+}
+
+#[cfg(feature = "async")]
+fn block_on<F: core::future::Future>(fut: F) -> F::Output {
+  use core::task::{Context, Poll, Waker};
+
+  let mut fut = core::pin::pin!(fut);
+  let mut cx = Context::from_waker(Waker::noop());
+  loop {
+    if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+      return output
+    }
+  }
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn filter_stream_passes_errors_through() {
+  use futures_util::{stream, StreamExt};
+
+  use crate::dsp::{filter_stream, Boxcar};
+
+  let mut adc =
+    converting(&[start(ConversionRate::Hz125, 0), fresh(2), sample(Stat::RDY, 100, true), fresh(4), standby()]);
+
+  let mut samples = adc.samples(&mut Immediate).take(3).collect::<Vec<_>>();
+  samples.insert(1, Err(Error::CalibrationOverrange));
+
+  let results = block_on(filter_stream(stream::iter(samples), Boxcar::<2>::new()).collect::<Vec<_>>());
+  assert!(matches!(results[..], [Err(Error::CalibrationOverrange), Ok(sample)] if sample.value() == 3));
+  done(adc);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn sample_stream_skips_unsettled_results() {
+  use futures_util::StreamExt;
+
+  /// A pin which is always low, counting how often it was waited for.
+  struct Low(usize);
+
+  impl embedded_hal::digital::ErrorType for Low {
+    type Error = core::convert::Infallible;
+  }
+
+  impl embedded_hal_async::digital::Wait for Low {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+      unreachable!()
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+      self.0 += 1;
+      Ok(())
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+      unreachable!()
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+      unreachable!()
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+      unreachable!()
+    }
+  }
+
+  let mut adc = standby_adc(&[
+    start(ConversionRate::Hz125, 0b00000010),
+    fresh(1),
+    fresh(2),
+    fresh(3),
+    sample(Stat::empty(), 3, false),
+    fresh(4),
+    standby(),
+  ]);
+  adc.set_skip_unsettled(true);
+  let mut adc = adc.start_conversion(ConversionRate::Hz125, true).unwrap();
+
+  let mut rdy = Low(0);
+  let samples = block_on(adc.sample_stream(&mut rdy).take(2).collect::<Vec<_>>());
+
+  // The first three results are not settled with the SINC filter, stale results are passed through.
+  let samples = samples.into_iter().map(Result::unwrap).collect::<Vec<_>>();
+  assert!(!samples[0].is_fresh());
+  assert_eq!(samples[1].value(), 4);
+  assert_eq!(rdy.0, 5);
+  done(adc);
+}
+
+#[test]
+fn overlapping_allan_matches_direct_computation() {
+  use crate::{
+    allan::{overlapping_deviation, OverlappingAllan},
+    ConversionMode,
+  };
+
+  // Results close to full scale after a full-scale step, with a small pseudo-random noise.
+  let mut seed = 1u32;
+  let values = (0..4096)
+    .map(|i| {
+      seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+      if i == 0 {
+        -0x800000
+      } else {
+        0x7FFFF0 + (seed >> 28) as i32
+      }
+    })
+    .collect::<Vec<_>>();
+
+  let mut adev = OverlappingAllan::<64>::new(ConversionRate::Hz1000, ConversionMode::Continuous);
+  for &value in &values {
+    adev.push(value);
+  }
+
+  let points = adev.points().collect::<Vec<_>>();
+  assert_eq!(points.len(), 5);
+
+  for point in points {
+    let m = point.m as usize;
+    let expected = overlapping_deviation(&values, m).unwrap();
+
+    assert_eq!(point.count, (values.len() - 2 * m + 1) as u64);
+    assert!((point.deviation - expected).abs() <= expected * 1e-5, "m = {m}: {} != {expected}", point.deviation);
+  }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn collect_histogram_counts_fresh_results() {
+  use crate::histogram::Histogram;
+
+  let mut adc = converting(&[
+    start(ConversionRate::Hz125, 0),
+    fresh(1),
+    sample(Stat::RDY, 2, true),
+    sample(Stat::empty(), 2, false),
+    sample(Stat::RDY | Stat::DOR, 0x7FFFFF, false),
+    fresh(1),
+    fresh(3),
+    standby(),
+  ]);
+
+  let mut histogram = Histogram::new(0..=3);
+  adc.collect_histogram(&mut Immediate, &mut histogram, 4).unwrap();
+
+  // Collided and stale results are not counted, clipped results fall outside of the window.
+  assert_eq!(histogram.total(), 4);
+  assert_eq!((histogram.hits(1), histogram.hits(2), histogram.hits(3)), (2, 0, 1));
+  done(adc);
+}
@@ -0,0 +1,109 @@
+use embedded_hal::spi::SpiDevice;
+
+use crate::{DataReady, Error, Sample, Samples};
+
+/// A monotonic clock.
+pub trait Clock {
+  /// Get the current time in nanoseconds.
+  fn now_ns(&mut self) -> u64;
+}
+
+/// A source of sample timestamps.
+///
+/// This is implemented for [`Nominal`] and all types implementing [`Clock`].
+pub trait Timebase {
+  /// Get the timestamp in nanoseconds of the conversion result with the given index.
+  fn timestamp_ns(&mut self, index: u64, period_ns: u64) -> u64;
+}
+
+/// Derive timestamps from the nominal conversion rate, starting at zero.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Nominal;
+
+impl Timebase for Nominal {
+  fn timestamp_ns(&mut self, index: u64, period_ns: u64) -> u64 {
+    index * period_ns
+  }
+}
+
+impl<C> Timebase for C
+where
+  C: Clock,
+{
+  fn timestamp_ns(&mut self, _index: u64, _period_ns: u64) -> u64 {
+    self.now_ns()
+  }
+}
+
+/// A conversion result with its index and timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimestampedSample {
+  /// Index of the conversion result since the start of the iteration.
+  pub index: u64,
+  /// Timestamp in nanoseconds.
+  pub timestamp_ns: u64,
+  /// Number of conversion results missed before this one.
+  pub missed: u64,
+  /// The conversion result.
+  pub sample: Sample,
+}
+
+impl TimestampedSample {
+  /// Check if conversion results were missed before this one.
+  pub const fn is_discontinuity(&self) -> bool {
+    self.missed > 0
+  }
+}
+
+/// An iterator over timestamped conversion results.
+///
+/// Created by [`Max11214::timestamped_samples`](crate::Max11214::timestamped_samples).
+///
+/// Conversion results which were overwritten while being read are skipped and counted as missed. When using a
+/// [`Clock`], conversion results which were overwritten before being read are detected by comparing the time
+/// between results with the nominal conversion period.
+#[derive(Debug)]
+pub struct Timestamped<'a, SPI, R, T, const N: usize = 0> {
+  pub(crate) samples: Samples<'a, SPI, R, N>,
+  pub(crate) timebase: T,
+  pub(crate) period_ns: u64,
+  pub(crate) next_index: u64,
+  pub(crate) last_timestamp_ns: Option<u64>,
+  pub(crate) skipped: u64,
+}
+
+impl<SPI, R, T, const N: usize> Iterator for Timestamped<'_, SPI, R, T, N>
+where
+  SPI: SpiDevice<u8>,
+  R: DataReady,
+  T: Timebase,
+{
+  type Item = Result<TimestampedSample, Error<SPI::Error>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let mut missed = core::mem::take(&mut self.skipped);
+
+    let sample = loop {
+      match self.samples.next()? {
+        Ok(sample) if sample.is_collided() => missed += 1,
+        Ok(sample) if sample.is_fresh() => break sample,
+        Ok(_) => continue,
+        Err(err) => return Some(Err(err)),
+      }
+    };
+
+    let timestamp_ns = self.timebase.timestamp_ns(self.next_index + missed, self.period_ns);
+
+    if let Some(last_timestamp_ns) = self.last_timestamp_ns {
+      let elapsed_ns = timestamp_ns.saturating_sub(last_timestamp_ns);
+      let periods = (elapsed_ns + self.period_ns / 2) / self.period_ns;
+      missed = missed.max(periods.saturating_sub(1));
+    }
+
+    let index = self.next_index + missed;
+    self.next_index = index + 1;
+    self.last_timestamp_ns = Some(timestamp_ns);
+
+    Some(Ok(TimestampedSample { index, timestamp_ns, missed, sample }))
+  }
+}
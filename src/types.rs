@@ -1,8 +1,10 @@
+use core::fmt;
+
 use crate::register::Stat;
 
 /// A 24-bit unsigned integer.
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub(crate) struct u24([u8; 3]);
 
 impl u24 {
@@ -10,6 +12,23 @@ impl u24 {
   pub(crate) const fn from_be_bytes(bytes: [u8; 3]) -> Self {
     Self(bytes)
   }
+
+  /// Create an integer from the lower 24 bits of a `u32`.
+  pub(crate) const fn truncate(n: u32) -> Self {
+    let [_, b2, b1, b0] = n.to_be_bytes();
+    Self([b2, b1, b0])
+  }
+
+  /// Return the memory representation of this integer as a byte array in big endian.
+  pub(crate) const fn to_be_bytes(self) -> [u8; 3] {
+    self.0
+  }
+}
+
+impl fmt::Debug for u24 {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{:#08X}", u32::from(*self))
+  }
 }
 
 impl From<u24> for u32 {
@@ -56,6 +75,164 @@ pub enum ConversionRate {
   Hz32000 = 0b1111,
 }
 
+impl ConversionRate {
+  /// All conversion rates, from slowest to fastest.
+  pub const ALL: [Self; 16] = [
+    Self::Hz0_95,
+    Self::Hz1_9,
+    Self::Hz3_9,
+    Self::Hz7_8,
+    Self::Hz15_6,
+    Self::Hz31_25,
+    Self::Hz62_5,
+    Self::Hz125,
+    Self::Hz250,
+    Self::Hz500,
+    Self::Hz1000,
+    Self::Hz2000,
+    Self::Hz4000,
+    Self::Hz8000,
+    Self::Hz16000,
+    Self::Hz32000,
+  ];
+
+  /// Nominal system clock frequency in hertz for which the conversion rates are specified.
+  pub const NOMINAL_CLOCK_HZ: u32 = 8_192_000;
+
+  /// Iterate over all conversion rates, from slowest to fastest.
+  pub fn iter() -> impl Iterator<Item = Self> {
+    Self::ALL.into_iter()
+  }
+
+  /// Check if the FIR filter can be used with this conversion rate.
+  pub const fn supports_fir(self) -> bool {
+    matches!(
+      self,
+      Self::Hz62_5
+        | Self::Hz125
+        | Self::Hz250
+        | Self::Hz500
+        | Self::Hz1000
+        | Self::Hz2000
+        | Self::Hz4000
+        | Self::Hz8000
+    )
+  }
+
+  /// Get the nominal conversion rate.
+  pub const fn hz(self, mode: ConversionMode) -> Hertz {
+    Hertz::new(1_000_000_000, self.period_ns(mode))
+  }
+
+  /// Get the expected conversion rate when using an external clock with the given frequency.
+  ///
+  /// The conversion rate scales linearly with the system clock frequency.
+  pub const fn hz_with_clock(self, mode: ConversionMode, clock_hz: u32) -> Hertz {
+    let Hertz { num, den } = self.hz(mode);
+    Hertz::new(num * clock_hz as u64, den * Self::NOMINAL_CLOCK_HZ as u64)
+  }
+
+  /// Find the conversion rate closest to the given rate in millihertz.
+  ///
+  /// ```rust
+  /// use max112x::{ConversionMode, ConversionRate};
+  ///
+  /// let rate = ConversionRate::from_hz_nearest(60_000, ConversionMode::Continuous);
+  /// assert_eq!(rate, ConversionRate::Hz62_5);
+  /// assert_eq!(rate.hz(ConversionMode::Continuous).millihertz(), 62_500);
+  /// ```
+  pub fn from_hz_nearest(millihertz: u32, mode: ConversionMode) -> Self {
+    Self::iter().min_by_key(|rate| rate.hz(mode).millihertz().abs_diff(u64::from(millihertz))).unwrap_or(Self::Hz0_95)
+  }
+
+  /// Get the nominal time between conversion results in nanoseconds.
+  pub const fn period_ns(self, mode: ConversionMode) -> u64 {
+    match mode {
+      ConversionMode::Continuous => 31_250 << (15 - self as u8),
+      ConversionMode::SingleCycle => match self {
+        Self::Hz0_95 => 40_000_000,
+        Self::Hz1_9 => 32_000_000,
+        Self::Hz3_9 => 20_000_000,
+        Self::Hz7_8 => 16_000_000,
+        Self::Hz15_6 => 10_000_000,
+        Self::Hz31_25 => 8_000_000,
+        Self::Hz62_5 => 5_000_000,
+        Self::Hz125 => 4_000_000,
+        Self::Hz250 => 2_500_000,
+        Self::Hz500 => 2_000_000,
+        Self::Hz1000 => 1_250_000,
+        Self::Hz2000 => 1_000_000,
+        Self::Hz4000 => 625_000,
+        Self::Hz8000 => 500_000,
+        Self::Hz16000 => 312_500,
+        Self::Hz32000 => 156_250,
+      },
+    }
+  }
+}
+
+/// A frequency in hertz, represented as a fraction.
+#[derive(Debug, Clone, Copy)]
+pub struct Hertz {
+  /// Numerator.
+  pub num: u64,
+  /// Denominator.
+  pub den: u64,
+}
+
+impl Hertz {
+  /// Create a frequency of `num / den` hertz.
+  pub const fn new(num: u64, den: u64) -> Self {
+    let (mut a, mut b) = (num, den);
+    while b != 0 {
+      (a, b) = (b, a % b);
+    }
+
+    if a <= 1 {
+      return Self { num, den }
+    }
+
+    Self { num: num / a, den: den / a }
+  }
+
+  /// Get the frequency in millihertz, rounded to the nearest integer.
+  pub const fn millihertz(self) -> u64 {
+    (self.num * 1000 + self.den / 2) / self.den
+  }
+
+  /// Get the frequency in hertz as a floating point number.
+  pub fn to_f32(self) -> f32 {
+    self.num as f32 / self.den as f32
+  }
+}
+
+impl PartialEq for Hertz {
+  fn eq(&self, other: &Self) -> bool {
+    u128::from(self.num) * u128::from(other.den) == u128::from(other.num) * u128::from(self.den)
+  }
+}
+
+impl Eq for Hertz {}
+
+/// Conversion mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionMode {
+  /// Continuous conversion using the SINC or FIR filter.
+  Continuous,
+  /// Single-cycle conversion.
+  SingleCycle,
+}
+
+impl ConversionMode {
+  pub(crate) const fn from_continuous(continuous: bool) -> Self {
+    if continuous {
+      Self::Continuous
+    } else {
+      Self::SingleCycle
+    }
+  }
+}
+
 /// Range format.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Format {
@@ -74,8 +251,79 @@ pub enum ClockSource {
   Internal,
 }
 
-/// PGA gain.
+/// Synchronization mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+  /// Pulse synchronization mode.
+  Pulse,
+  /// Continuous synchronization mode.
+  Continuous,
+}
+
+/// Input range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Range {
+  /// Bipolar input range (±VREF).
+  Bipolar,
+  /// Unipolar input range (0 to VREF).
+  Unipolar,
+}
+
+/// Modulator digital gain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigitalGain {
+  /// × 1
+  X1,
+  /// × 2
+  X2,
+  /// × 4
+  X4,
+  /// × 8
+  X8,
+}
+
+impl DigitalGain {
+  /// Get the gain factor.
+  pub const fn gain(self) -> u8 {
+    1 << self as u8
+  }
+}
+
+/// Digital filter.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+  /// FIR filter.
+  Fir,
+  /// FIR filter followed by the IIR highpass filter.
+  FirHighpass,
+  /// SINC filter.
+  Sinc,
+  /// SINC filter followed by the IIR highpass filter.
+  SincHighpass,
+}
+
+/// FIR filter phase response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirPhase {
+  /// Linear phase.
+  Linear,
+  /// Minimum phase.
+  Minimum,
+}
+
+/// General-purpose I/O pin configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gpio {
+  /// Use as input.
+  Input,
+  /// Use as output driven low.
+  OutputLow,
+  /// Use as output driven high.
+  OutputHigh,
+}
+
+/// PGA gain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Pga {
   /// × 1
   X1,
@@ -95,6 +343,32 @@ pub enum Pga {
   X128,
 }
 
+impl Pga {
+  /// All PGA gains, from lowest to highest.
+  pub const ALL: [Self; 8] = [Self::X1, Self::X2, Self::X4, Self::X8, Self::X16, Self::X32, Self::X64, Self::X128];
+
+  /// Get the gain factor.
+  pub const fn gain(self) -> u8 {
+    1 << self as u8
+  }
+
+  /// Get the next higher gain, if any.
+  pub const fn higher(self) -> Option<Self> {
+    match self {
+      Self::X128 => None,
+      _ => Some(Self::ALL[self as usize + 1]),
+    }
+  }
+
+  /// Get the next lower gain, if any.
+  pub const fn lower(self) -> Option<Self> {
+    match self {
+      Self::X1 => None,
+      _ => Some(Self::ALL[self as usize - 1]),
+    }
+  }
+}
+
 /// System status.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Status {
@@ -125,7 +399,7 @@ impl Status {
 
   /// Check if the system gain calibration was overranged.
   pub const fn system_gain_overrange(&self) -> bool {
-    self.status.contains(Stat::DOR)
+    self.status.contains(Stat::SYSGOR)
   }
 
   /// Get the conversion rate that corresponds to the result in the DATA register or the rate that was used for
@@ -178,3 +452,13 @@ pub enum Calibration {
   /// System-level full-scale calibration.
   SystemFullScaleCalibration,
 }
+
+impl Calibration {
+  /// Get the time in nanoseconds the calibration takes.
+  pub const fn duration_ns(self) -> u32 {
+    match self {
+      Self::SelfCalibration => 200_000_000,
+      Self::SystemOffsetCalibration | Self::SystemFullScaleCalibration => 100_000_000,
+    }
+  }
+}